@@ -1,6 +1,58 @@
+use std::path::PathBuf;
+use std::str::FromStr;
 use structopt::StructOpt;
 
 #[derive(StructOpt)]
 pub struct UserInput {
-    pub equation: String,
+    pub equation: Option<String>,
+    #[structopt(long, default_value = "text", possible_values = &["text", "json"])]
+    pub format: Format,
+    #[structopt(long)]
+    pub repl: bool,
+    #[structopt(long)]
+    pub exact: bool,
+    #[structopt(long, default_value = "1e-12", parse(try_from_str = parse_epsilon))]
+    pub epsilon: f64,
+    #[structopt(long, parse(try_from_str = parse_variable))]
+    pub var: Option<char>,
+    #[structopt(long)]
+    pub strict: bool,
+    #[structopt(long, parse(from_os_str))]
+    pub batch: Option<PathBuf>,
+}
+
+#[derive(Copy, Clone, PartialEq)]
+pub enum Format {
+    Text,
+    Json,
+}
+
+pub fn parse_epsilon(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(epsilon) if epsilon >= 0.0 && epsilon.is_finite() => Ok(epsilon),
+        _ => Err(format!("'{}' is not a valid epsilon", s)),
+    }
+}
+
+pub fn parse_variable(s: &str) -> Result<char, String> {
+    let mut chars = s.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if c.is_ascii_alphabetic() => Ok(c),
+        _ => Err(format!(
+            "'{}' is not a valid variable, it must be a single letter",
+            s
+        )),
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            _ => Err(format!("'{}' is not a valid output format", s)),
+        }
+    }
 }
@@ -0,0 +1,137 @@
+use std::fmt;
+
+pub struct Equation {
+    pub lhs: Expr,
+    pub rhs: Expr,
+}
+
+pub enum Expr {
+    Number {
+        value: f64,
+        index: usize,
+        len: usize,
+    },
+    Identifier {
+        name: char,
+        index: usize,
+        len: usize,
+    },
+    Group {
+        inner: Box<Expr>,
+        index: usize,
+        len: usize,
+    },
+    Neg {
+        operand: Box<Expr>,
+        index: usize,
+        len: usize,
+    },
+    Add {
+        left: Box<Expr>,
+        right: Box<Expr>,
+        index: usize,
+        len: usize,
+    },
+    Sub {
+        left: Box<Expr>,
+        right: Box<Expr>,
+        index: usize,
+        len: usize,
+    },
+    Mult {
+        left: Box<Expr>,
+        right: Box<Expr>,
+        index: usize,
+        len: usize,
+    },
+    Div {
+        left: Box<Expr>,
+        right: Box<Expr>,
+        index: usize,
+        len: usize,
+    },
+    Power {
+        base: Box<Expr>,
+        exponent: u32,
+        index: usize,
+        len: usize,
+    },
+}
+
+impl Expr {
+    pub fn get_index(&self) -> usize {
+        match self {
+            Expr::Number { index, .. } => *index,
+            Expr::Identifier { index, .. } => *index,
+            Expr::Group { index, .. } => *index,
+            Expr::Neg { index, .. } => *index,
+            Expr::Add { index, .. } => *index,
+            Expr::Sub { index, .. } => *index,
+            Expr::Mult { index, .. } => *index,
+            Expr::Div { index, .. } => *index,
+            Expr::Power { index, .. } => *index,
+        }
+    }
+
+    pub fn get_len(&self) -> usize {
+        match self {
+            Expr::Number { len, .. } => *len,
+            Expr::Identifier { len, .. } => *len,
+            Expr::Group { len, .. } => *len,
+            Expr::Neg { len, .. } => *len,
+            Expr::Add { len, .. } => *len,
+            Expr::Sub { len, .. } => *len,
+            Expr::Mult { len, .. } => *len,
+            Expr::Div { len, .. } => *len,
+            Expr::Power { len, .. } => *len,
+        }
+    }
+
+    pub fn get_end(&self) -> usize {
+        self.get_index() + self.get_len()
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Expr::Number { value, .. } => write!(f, "{}", value),
+            Expr::Identifier { name, .. } => write!(f, "{}", name),
+            Expr::Group { inner, .. } => write!(f, "({})", inner),
+            Expr::Neg { operand, .. } => write!(f, "-{}", operand),
+            Expr::Add { left, right, .. } => write!(f, "{} + {}", left, right),
+            Expr::Sub { left, right, .. } => write!(f, "{} - {}", left, right),
+            Expr::Mult { left, right, .. } => write!(f, "{} * {}", left, right),
+            Expr::Div { left, right, .. } => write!(f, "{} / {}", left, right),
+            Expr::Power { base, exponent, .. } => write!(f, "{}^{}", base, exponent),
+        }
+    }
+}
+
+impl fmt::Debug for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Expr::Number { value, .. } => write!(f, "Number({})", value),
+            Expr::Identifier { name, .. } => write!(f, "{}", name),
+            Expr::Group { inner, .. } => write!(f, "Group({:?})", inner),
+            Expr::Neg { operand, .. } => write!(f, "Neg({:?})", operand),
+            Expr::Add { left, right, .. } => write!(f, "Add({:?}, {:?})", left, right),
+            Expr::Sub { left, right, .. } => write!(f, "Sub({:?}, {:?})", left, right),
+            Expr::Mult { left, right, .. } => write!(f, "Mult({:?}, {:?})", left, right),
+            Expr::Div { left, right, .. } => write!(f, "Div({:?}, {:?})", left, right),
+            Expr::Power { base, exponent, .. } => write!(f, "Power({:?}, {})", base, exponent),
+        }
+    }
+}
+
+impl fmt::Display for Equation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} = {}", self.lhs, self.rhs)
+    }
+}
+
+impl fmt::Debug for Equation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Equation({:?}, {:?})", self.lhs, self.rhs)
+    }
+}
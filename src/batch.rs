@@ -0,0 +1,81 @@
+use std::io;
+use std::io::BufRead;
+
+use crate::args::Format;
+use crate::json;
+use crate::pipeline;
+use crate::pipeline::Settings;
+use crate::solver::Roots;
+
+#[derive(Default)]
+pub struct Summary {
+    pub solved: usize,
+    pub no_solution: usize,
+    pub infinite: usize,
+    pub errors: usize,
+}
+
+impl Summary {
+    fn print(&self, settings: &Settings) {
+        match settings.format {
+            Format::Text => println!(
+                "Summary: {} solved, {} without solution, {} with infinitely many solutions, {} error(s)",
+                self.solved, self.no_solution, self.infinite, self.errors
+            ),
+            Format::Json => println!(
+                "{{\"summary\":{{\"solved\":{},\"no_solution\":{},\"infinite\":{},\"errors\":{}}}}}",
+                self.solved, self.no_solution, self.infinite, self.errors
+            ),
+        }
+    }
+}
+
+fn solve_line(line_nb: usize, equation: &str, settings: &Settings, summary: &mut Summary) {
+    if settings.format == Format::Text {
+        println!("Line {}: {}", line_nb, equation);
+    }
+    match pipeline::solve(equation, settings) {
+        Ok((solver, solution)) => {
+            match solution.roots {
+                Roots::None => summary.no_solution += 1,
+                Roots::AllReals => summary.infinite += 1,
+                Roots::Roots(_) => summary.solved += 1,
+            }
+            match settings.format {
+                Format::Text => pipeline::describe(solver.as_ref(), &solution, settings.precision),
+                Format::Json => println!(
+                    "{{\"line\":{},\"result\":{}}}",
+                    line_nb,
+                    json::solution(&solution)
+                ),
+            }
+        }
+        Err(err) => {
+            summary.errors += 1;
+            match settings.format {
+                Format::Text => println!("{}", pipeline::render_error(equation, &err, settings)),
+                Format::Json => println!(
+                    "{{\"line\":{},\"result\":{}}}",
+                    line_nb,
+                    pipeline::render_error(equation, &err, settings)
+                ),
+            }
+        }
+    }
+    if settings.format == Format::Text {
+        println!();
+    }
+}
+
+pub fn run<R: BufRead>(reader: R, settings: &Settings) -> io::Result<Summary> {
+    let mut summary = Summary::default();
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+        solve_line(i + 1, &line, settings, &mut summary);
+    }
+    summary.print(settings);
+    Ok(summary)
+}
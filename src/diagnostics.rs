@@ -0,0 +1,152 @@
+use crate::lexer::{Lexem, LexicalError};
+use crate::parser::ParseError;
+
+const MARGIN: &str = "    ";
+
+fn char_boundary(equation: &str, index: usize) -> usize {
+    let mut index = index.min(equation.len());
+    while !equation.is_char_boundary(index) {
+        index += 1;
+    }
+    index
+}
+
+fn underline(equation: &str, index: usize, len: usize) -> String {
+    let start = char_boundary(equation, index);
+    let end = char_boundary(equation, index + len);
+    let column = equation[..start].chars().count();
+    let width = equation[start..end].chars().count().max(1);
+    format!("{}^{}", " ".repeat(column), "~".repeat(width - 1))
+}
+
+fn previous_char(equation: &str, index: usize) -> Option<char> {
+    equation[..char_boundary(equation, index)]
+        .trim_end()
+        .chars()
+        .last()
+}
+
+fn count(equation: &str, c: char) -> usize {
+    equation.chars().filter(|other| *other == c).count()
+}
+
+fn power_hint(equation: &str, index: usize, exponent: &str) -> String {
+    match previous_char(equation, index) {
+        Some(c) if c.is_ascii_alphabetic() => format!("did you mean `{}^{}`?", c, exponent),
+        _ => format!("did you mean `^{}`? Powers are written with `^`", exponent),
+    }
+}
+
+fn lexical_hint(equation: &str, err: &LexicalError) -> Option<String> {
+    let index = err.get_index();
+    let c = equation[char_boundary(equation, index)..].chars().next()?;
+    match c {
+        '²' => Some(power_hint(equation, index, "2")),
+        '³' => Some(power_hint(equation, index, "3")),
+        ',' => Some(String::from(
+            "did you mean `.`? Decimal numbers are written with a dot",
+        )),
+        _ => None,
+    }
+}
+
+fn parse_hint(equation: &str, err: &ParseError) -> Option<String> {
+    let lexem = match err {
+        ParseError::UnexpectedToken(lexem) => lexem,
+        ParseError::NotUIntegerDegree(_) => {
+            return Some(String::from(
+                "degrees must be non-negative integers, e.g. `X^2`",
+            ))
+        }
+        ParseError::OtherVariable(_, variable) => {
+            return Some(format!(
+                "an equation has a single unknown, here `{}`",
+                variable
+            ))
+        }
+        ParseError::ImplicitMultiplication(Lexem::Identifier { name, .. }) => {
+            return Some(format!("did you mean `* {}`?", name))
+        }
+        ParseError::ImplicitMultiplication(_) => return Some(String::from("did you mean `* (`?")),
+        _ => return None,
+    };
+    let index = lexem.get_index();
+    match lexem {
+        Lexem::Number { .. }
+            if previous_char(equation, index).is_some_and(|c| c.is_ascii_alphabetic()) =>
+        {
+            let exponent = &equation[index..index + lexem.get_len()];
+            Some(format!(
+                "did you mean `{}^{}`?",
+                previous_char(equation, index).unwrap_or('X'),
+                exponent
+            ))
+        }
+        Lexem::Mult { .. } if equation[..index].ends_with('*') => Some(String::from(
+            "did you mean `^`? Powers are written with `^`",
+        )),
+        Lexem::End { .. } if !equation.contains('=') => Some(String::from(
+            "an equation needs an `=` sign, e.g. `X^2 = 4`",
+        )),
+        Lexem::End { .. } | Lexem::Equal { .. } if count(equation, '(') > count(equation, ')') => {
+            Some(String::from("a `)` is missing"))
+        }
+        Lexem::CloseParenthesis { .. } if count(equation, ')') > count(equation, '(') => {
+            Some(String::from("this `)` has no matching `(`"))
+        }
+        Lexem::Equal { .. } if count(equation, '=') > 1 => {
+            Some(String::from("an equation has a single `=` sign"))
+        }
+        _ => None,
+    }
+}
+
+fn render(
+    equation: &str,
+    message: String,
+    span: Option<(usize, usize)>,
+    hint: Option<String>,
+) -> String {
+    let mut output = message;
+    if let Some((index, len)) = span {
+        output.push_str(&format!(
+            "\n{}{}\n{}{}",
+            MARGIN,
+            equation,
+            MARGIN,
+            underline(equation, index, len)
+        ));
+    }
+    if let Some(hint) = hint {
+        output.push_str(&format!("\nHint: {}", hint));
+    }
+    output
+}
+
+pub fn lexical_errors(equation: &str, errs: &[LexicalError]) -> String {
+    errs.iter()
+        .map(|err| {
+            render(
+                equation,
+                format!("Error: {:?}", err),
+                Some(err.get_span()),
+                lexical_hint(equation, err),
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+pub fn parse_errors(equation: &str, errs: &[ParseError]) -> String {
+    errs.iter()
+        .map(|err| {
+            render(
+                equation,
+                format!("Error: {:?}", err),
+                err.get_span(),
+                parse_hint(equation, err),
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
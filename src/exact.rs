@@ -0,0 +1,275 @@
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::{ToPrimitive, Zero};
+
+use crate::ast::Equation;
+use crate::factor;
+use crate::lexer::Lexem;
+use crate::maths;
+use crate::maths::{Complex, Polynomial, Scaled, Surd};
+use crate::parser;
+use crate::parser::{ParseError, Reduction};
+use crate::solver;
+use crate::solver::{Root, Roots, Solution, SolveError, Solver};
+
+pub fn reduce(source: &str, equation: &Equation) -> Result<Reduction<BigRational>, ParseError> {
+    parser::reduce(equation, &|value, index, len| {
+        source
+            .get(index..index + len)
+            .and_then(maths::parse_literal)
+            .ok_or(ParseError::UnexpectedToken(Lexem::Number {
+                value,
+                index,
+                len,
+            }))
+    })
+}
+
+pub fn to_float(polynomial: &Polynomial<BigRational>) -> Polynomial {
+    polynomial.map(|coeff| coeff.to_f64().unwrap_or(f64::NAN))
+}
+
+enum ExactRoots {
+    None,
+    AllReals,
+    Rationals(Vec<BigRational>),
+    Surds(Box<(Surd, Surd)>),
+}
+
+pub struct ExactSolver {
+    polynomial: Polynomial<BigRational>,
+    degree: u32,
+    delta: Option<BigRational>,
+    roots: ExactRoots,
+}
+
+impl ExactSolver {
+    pub fn new(polynomial: Polynomial<BigRational>) -> Self {
+        ExactSolver {
+            degree: polynomial.degree(),
+            polynomial,
+            delta: None,
+            roots: ExactRoots::None,
+        }
+    }
+
+    fn coeff(&self, degree: u32) -> BigRational {
+        self.polynomial.coeff(degree)
+    }
+
+    fn solve_quadratic(&mut self) {
+        let (a, b, c) = (self.coeff(2), self.coeff(1), self.coeff(0));
+        let delta = &b * &b - BigRational::from_integer(BigInt::from(4)) * &a * &c;
+        self.roots = match delta.is_zero() {
+            true => ExactRoots::Rationals(vec![-&b / (&a + &a)]),
+            false => ExactRoots::Surds(Box::new(maths::quadratic_roots(&a, &b, &c))),
+        };
+        self.delta = Some(delta);
+    }
+}
+
+impl Solver for ExactSolver {
+    fn solve(&mut self, polynomial: &Polynomial, _epsilon: f64) -> Result<Solution, SolveError> {
+        match self.degree {
+            0 => {
+                self.roots = match self.coeff(0).is_zero() {
+                    true => ExactRoots::AllReals,
+                    false => ExactRoots::None,
+                }
+            }
+            1 => self.roots = ExactRoots::Rationals(vec![-self.coeff(0) / self.coeff(1)]),
+            _ => self.solve_quadratic(),
+        }
+        let roots = match &self.roots {
+            ExactRoots::None => Roots::None,
+            ExactRoots::AllReals => Roots::AllReals,
+            ExactRoots::Rationals(roots) => Roots::Roots(
+                roots
+                    .iter()
+                    .map(|root| {
+                        let value = Complex {
+                            real: root.to_f64().unwrap_or(f64::NAN),
+                            imag: 0.0,
+                        };
+                        match self.degree == 2 && roots.len() == 1 {
+                            true => Root::new(value, 2),
+                            false => Root::new(value, 1),
+                        }
+                    })
+                    .collect(),
+            ),
+            ExactRoots::Surds(roots) => Roots::Roots(vec![
+                Root::new(roots.0.to_complex(), 1),
+                Root::new(roots.1.to_complex(), 1),
+            ]),
+        };
+        Ok(Solution {
+            polynomial: polynomial.clone(),
+            degree: self.degree,
+            discriminant: self
+                .delta
+                .as_ref()
+                .and_then(|delta| delta.to_f64())
+                .map(|delta| Scaled::new(delta, 0)),
+            roots,
+            denominators: Vec::new(),
+            rejected: Vec::new(),
+            variable: 'X',
+            convergence: None,
+            factorisation: factor::factorise(&self.polynomial),
+        })
+    }
+
+    fn describe(&self, solution: &Solution, precision: usize) {
+        let variable = solution.variable;
+        println!("Reduced form: {} = 0", self.polynomial.named(variable));
+        solver::describe_factored_form(solution);
+        println!("Polynomial degree: {}", self.degree);
+        match self.degree {
+            0 => match self.roots {
+                ExactRoots::AllReals => println!("All real numbers are solution"),
+                _ => println!("No real number is solution"),
+            },
+            1 => {
+                println!("a = {}, b = {}", self.coeff(1), self.coeff(0));
+                println!("The solution is:");
+                if let ExactRoots::Rationals(roots) = &self.roots {
+                    println!("-b / a = {}", roots[0]);
+                }
+            }
+            _ => {
+                println!(
+                    "a = {}, b = {}, c = {}",
+                    self.coeff(2),
+                    self.coeff(1),
+                    self.coeff(0)
+                );
+                if let Some(delta) = &self.delta {
+                    println!("Δ = b^2 - 4ac = {}", delta);
+                }
+                match &self.roots {
+                    ExactRoots::Rationals(roots) => {
+                        println!("Discriminant is zero, the solution is:");
+                        println!("-b / 2a = {}{}", roots[0], solver::format_multiplicity(2));
+                    }
+                    ExactRoots::Surds(roots) if roots.0.to_complex().imag != 0.0 => {
+                        println!(
+                            "Discriminant is strictly negative, the two complex solutions are:"
+                        );
+                        println!(
+                            "(-b - i√(-Δ)) / 2a = {}",
+                            solver::format_surd(&roots.0, precision)
+                        );
+                        println!(
+                            "(-b + i√(-Δ)) / 2a = {}",
+                            solver::format_surd(&roots.1, precision)
+                        );
+                    }
+                    ExactRoots::Surds(roots) => {
+                        println!("Discriminant is strictly positive, the two solutions are:");
+                        println!(
+                            "(-b - √Δ) / 2a = {}",
+                            solver::format_surd(&roots.0, precision)
+                        );
+                        println!(
+                            "(-b + √Δ) / 2a = {}",
+                            solver::format_surd(&roots.1, precision)
+                        );
+                    }
+                    _ => (),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::Lexer;
+    use crate::parser::Parser;
+
+    fn solve(equation: &str) -> (ExactSolver, Solution) {
+        let mut lexer = Lexer::new();
+        lexer.run(equation).unwrap();
+        let mut parser = Parser::new();
+        parser.run(lexer.get_lexems(), None, false).unwrap();
+        let polynomial = reduce(equation, parser.get_equation().unwrap())
+            .unwrap()
+            .polynomial;
+        let mut solver = ExactSolver::new(polynomial.clone());
+        let solution = solver.solve(&to_float(&polynomial), 0.0).unwrap();
+        (solver, solution)
+    }
+
+    fn rational(numer: i64, denom: i64) -> BigRational {
+        BigRational::new(BigInt::from(numer), BigInt::from(denom))
+    }
+
+    fn surds(solver: &ExactSolver) -> (String, String) {
+        match &solver.roots {
+            ExactRoots::Surds(roots) => (roots.0.to_string(), roots.1.to_string()),
+            _ => panic!("the roots are not surds"),
+        }
+    }
+
+    #[test]
+    fn decimal_noise_cancels_exactly() {
+        let (solver, solution) = solve("0.1 + 0.2 = 0.3");
+        assert!(matches!(solver.roots, ExactRoots::AllReals));
+        assert!(matches!(solution.roots, Roots::AllReals));
+        let (solver, _) = solve("0.1 X + 0.2 = 0.3");
+        assert!(
+            matches!(solver.roots, ExactRoots::Rationals(ref roots) if roots == &[rational(1, 1)])
+        );
+    }
+
+    #[test]
+    fn rational_roots() {
+        let (solver, _) = solve("3 * X = 1");
+        assert!(
+            matches!(solver.roots, ExactRoots::Rationals(ref roots) if roots == &[rational(1, 3)])
+        );
+        let (solver, solution) = solve("4 * X^2 - 4 * X + 1 = 0");
+        assert!(
+            matches!(solver.roots, ExactRoots::Rationals(ref roots) if roots == &[rational(1, 2)])
+        );
+        assert!(solver.delta.unwrap().is_zero());
+        match solution.roots {
+            Roots::Roots(roots) => {
+                assert_eq!(roots.len(), 1);
+                assert_eq!(roots[0].value.real, 0.5);
+                assert_eq!(roots[0].multiplicity, 2);
+            }
+            _ => panic!("the root is missing"),
+        }
+    }
+
+    #[test]
+    fn surd_roots() {
+        let (solver, _) = solve("X^2 - 2 = 0");
+        assert_eq!(surds(&solver), (String::from("-√2"), String::from("√2")));
+        let (solver, _) = solve("2 * X^2 + 2 * X - 1 = 0");
+        assert_eq!(
+            surds(&solver),
+            (String::from("(-1 - √3)/2"), String::from("(-1 + √3)/2"))
+        );
+        let (solver, _) = solve("X^2 - X/3 - 1/3 = 0");
+        assert_eq!(
+            surds(&solver),
+            (String::from("(1 - √13)/6"), String::from("(1 + √13)/6"))
+        );
+    }
+
+    #[test]
+    fn complex_surd_roots() {
+        let (solver, solution) = solve("X^2 + X + 1 = 0");
+        assert_eq!(
+            surds(&solver),
+            (String::from("(-1 - i√3)/2"), String::from("(-1 + i√3)/2"))
+        );
+        assert_eq!(solution.discriminant.unwrap().value, -3.0);
+        let (solver, _) = solve("X^2 + 4 = 0");
+        assert_eq!(surds(&solver), (String::from("-2i"), String::from("2i")));
+    }
+}
@@ -0,0 +1,311 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_rational::BigRational;
+use num_traits::{One, Signed, ToPrimitive, Zero};
+
+use crate::maths;
+use crate::maths::Polynomial;
+
+const DIVISOR_BOUND: u64 = 1_000_000;
+// Bits of the integer coefficients above which the gcds of Yun's algorithm get too slow
+const SIZE_BOUND: u64 = 768;
+
+// Computed once per equation, the factors also give the multiplicities of the roots
+#[derive(Clone)]
+pub struct Factorisation {
+    constant: BigRational,
+    factors: Vec<(Polynomial<BigRational>, usize)>,
+    variable: char,
+}
+
+impl Factorisation {
+    pub fn named(self, variable: char) -> Self {
+        Factorisation { variable, ..self }
+    }
+
+    // Coprime and square-free, the roots of a factor all have its multiplicity
+    pub fn float_factors(&self) -> Vec<(Polynomial, usize)> {
+        self.factors
+            .iter()
+            .map(|(part, multiplicity)| {
+                let part = part.map(|coeff| coeff.to_f64().unwrap_or(f64::NAN));
+                (part, *multiplicity)
+            })
+            .collect()
+    }
+}
+
+fn x() -> Polynomial<BigRational> {
+    Polynomial::monomial(BigRational::one(), 1)
+}
+
+fn divisors(n: &BigInt) -> Option<Vec<u64>> {
+    let n = n.abs().to_u64().filter(|n| *n <= DIVISOR_BOUND)?;
+    let mut divisors: Vec<u64> = (1..)
+        .take_while(|d| d * d <= n)
+        .filter(|d| n % d == 0)
+        .flat_map(|d| [d, n / d])
+        .collect();
+    divisors.sort_unstable();
+    divisors.dedup();
+    Some(divisors)
+}
+
+fn square_free(p: &Polynomial<BigRational>) -> Vec<(Polynomial<BigRational>, usize)> {
+    // Yun's algorithm
+    let derivative = p.derivative();
+    let b = p.gcd(&derivative);
+    let mut c = p.div_rem(&b).0;
+    let mut d = derivative.div_rem(&b).0 - c.derivative();
+    let mut parts = Vec::new();
+    let mut multiplicity = 1;
+    while c.degree() > 0 {
+        let a = c.gcd(&d);
+        c = c.div_rem(&a).0;
+        d = d.div_rem(&a).0 - c.derivative();
+        if a.degree() > 0 {
+            parts.push((a, multiplicity));
+        }
+        multiplicity += 1;
+    }
+    parts
+}
+
+fn rational_roots(p: &Polynomial<BigRational>) -> (Vec<BigRational>, Polynomial<BigRational>) {
+    let mut roots = Vec::new();
+    let mut rest = p.clone();
+    if rest.coeff(0).is_zero() {
+        roots.push(BigRational::zero());
+        rest = rest.div_rem(&x()).0;
+    }
+    let candidates = match (
+        divisors(rest.coeff(0).numer()),
+        divisors(rest.leading_coeff().numer()),
+    ) {
+        (Some(numerators), Some(denominators)) => {
+            let mut candidates: Vec<BigRational> = Vec::new();
+            for p in numerators.iter() {
+                for q in denominators.iter().filter(|q| p.gcd(*q) == 1) {
+                    let candidate = BigRational::new(BigInt::from(*p), BigInt::from(*q));
+                    candidates.push(-candidate.clone());
+                    candidates.push(candidate);
+                }
+            }
+            candidates.sort_by(compare_roots);
+            candidates
+        }
+        _ => Vec::new(),
+    };
+    for candidate in candidates.iter() {
+        if rest.degree() == 0 {
+            break;
+        }
+        if rest.evaluate(candidate).is_zero() {
+            rest = rest
+                .div_rem(&(x() - Polynomial::constant(candidate.clone())))
+                .0;
+            roots.push(candidate.clone());
+        }
+    }
+    if rest.degree() == 2 {
+        // Too big for the divisors, a quadratic still splits when its discriminant is a square
+        let rest_roots = quadratic_roots(&rest.primitive());
+        if !rest_roots.is_empty() {
+            roots.extend(rest_roots);
+            rest = Polynomial::constant(BigRational::one());
+        }
+    }
+    (roots, rest)
+}
+
+fn quadratic_roots(p: &Polynomial<BigRational>) -> Vec<BigRational> {
+    let (a, b, c) = (
+        p.coeff(2).numer().clone(),
+        p.coeff(1).numer().clone(),
+        p.coeff(0).numer().clone(),
+    );
+    let discriminant = &b * &b - BigInt::from(4) * &a * &c;
+    if discriminant.is_negative() {
+        return Vec::new();
+    }
+    let root = discriminant.sqrt();
+    if &root * &root != discriminant {
+        return Vec::new();
+    }
+    let mut roots = vec![
+        BigRational::new(-&b - &root, BigInt::from(2) * &a),
+        BigRational::new(-&b + &root, BigInt::from(2) * &a),
+    ];
+    roots.sort_by(compare_roots);
+    roots
+}
+
+fn compare_roots(a: &BigRational, b: &BigRational) -> Ordering {
+    a.abs()
+        .cmp(&b.abs())
+        .then(a.is_negative().cmp(&b.is_negative()))
+}
+
+fn compare_factors(a: &Polynomial<BigRational>, b: &Polynomial<BigRational>) -> Ordering {
+    let root = |p: &Polynomial<BigRational>| -p.coeff(0) / p.coeff(1);
+    match (a.degree(), b.degree()) {
+        (1, 1) => compare_roots(&root(a), &root(b)),
+        (a_degree, b_degree) => a_degree.cmp(&b_degree),
+    }
+}
+
+pub fn factorise(p: &Polynomial<BigRational>) -> Option<Factorisation> {
+    let size: u64 = p
+        .primitive()
+        .terms()
+        .map(|(_, coeff)| coeff.numer().bits())
+        .sum();
+    if p.degree() == 0 || size > SIZE_BOUND {
+        return None;
+    }
+    let mut factors = Vec::new();
+    for (part, multiplicity) in square_free(p) {
+        let (roots, rest) = rational_roots(&part.primitive());
+        for root in roots {
+            factors.push(((x() - Polynomial::constant(root)).primitive(), multiplicity));
+        }
+        if rest.degree() > 0 {
+            factors.push((rest.primitive(), multiplicity));
+        }
+    }
+    factors.sort_by(|(a, _), (b, _)| compare_factors(a, b));
+    let leading = factors
+        .iter()
+        .fold(BigRational::one(), |acc, (factor, multiplicity)| {
+            acc * num_traits::pow(factor.leading_coeff(), *multiplicity)
+        });
+    Some(Factorisation {
+        constant: p.leading_coeff() / leading,
+        factors,
+        variable: 'X',
+    })
+}
+
+fn to_rational(p: &Polynomial) -> Option<Polynomial<BigRational>> {
+    let mut rational = Polynomial::new();
+    for (degree, coeff) in p.terms() {
+        rational = rational + Polynomial::monomial(maths::to_rational(*coeff)?, degree);
+    }
+    Some(rational)
+}
+
+pub fn factorise_float(p: &Polynomial) -> Option<Factorisation> {
+    factorise(&to_rational(p)?)
+}
+
+fn format_factor(factor: &Polynomial<BigRational>, variable: char) -> String {
+    let mut output = String::new();
+    for (degree, coeff) in factor.terms().collect::<Vec<_>>().into_iter().rev() {
+        let sign = match (output.is_empty(), coeff.is_negative()) {
+            (true, true) => "-",
+            (true, false) => "",
+            (false, true) => " - ",
+            (false, false) => " + ",
+        };
+        let coeff = match coeff.abs().is_one() && degree > 0 {
+            true => String::new(),
+            false => coeff.abs().to_string(),
+        };
+        let unknown = match degree {
+            0 => String::new(),
+            1 => variable.to_string(),
+            _ => format!("{}^{}", variable, degree),
+        };
+        output.push_str(&format!("{}{}{}", sign, coeff, unknown));
+    }
+    output
+}
+
+impl fmt::Display for Factorisation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let [(factor, 1)] = self.factors.as_slice() {
+            if self.constant.is_one() {
+                return write!(f, "{}", format_factor(factor, self.variable));
+            }
+        }
+        if self.constant == -BigRational::one() {
+            write!(f, "-")?;
+        } else if self.constant.is_integer() && !self.constant.is_one() {
+            write!(f, "{}", self.constant)?;
+        } else if !self.constant.is_integer() {
+            write!(f, "{} * ", self.constant)?;
+        }
+        for (factor, multiplicity) in self.factors.iter() {
+            match factor.terms().count() {
+                1 => write!(f, "{}", format_factor(factor, self.variable))?,
+                _ => write!(f, "({})", format_factor(factor, self.variable))?,
+            }
+            if *multiplicity > 1 {
+                write!(f, "^{}", multiplicity)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn factorised(coeffs: &[f64]) -> Option<String> {
+        factorise_float(&Polynomial::from_coeffs(coeffs))
+            .map(|factorisation| factorisation.to_string())
+    }
+
+    #[test]
+    fn divisors_up_to_the_square_root() {
+        assert_eq!(divisors(&BigInt::from(1)), Some(vec![1]));
+        assert_eq!(divisors(&BigInt::from(-12)), Some(vec![1, 2, 3, 4, 6, 12]));
+        assert_eq!(divisors(&BigInt::from(49)), Some(vec![1, 7, 49]));
+        assert_eq!(divisors(&BigInt::from(999_983)), Some(vec![1, 999_983]));
+        assert_eq!(divisors(&BigInt::from(DIVISOR_BOUND + 1)), None);
+    }
+
+    #[test]
+    fn factored_forms() {
+        assert_eq!(factorised(&[-2.0, 0.0, 2.0]).unwrap(), "2(X - 1)(X + 1)");
+        assert_eq!(factorised(&[1.0, -2.0, 1.0]).unwrap(), "(X - 1)^2");
+        assert_eq!(
+            factorised(&[0.0, -1.0, 0.0, 1.0]).unwrap(),
+            "X(X - 1)(X + 1)"
+        );
+        assert_eq!(factorised(&[1.0, 0.0, 1.0]).unwrap(), "X^2 + 1");
+        assert_eq!(
+            factorised(&[0.25, 0.0, -1.0]).unwrap(),
+            "-1/4 * (2X - 1)(2X + 1)"
+        );
+        assert!(factorised(&[5.0]).is_none());
+        assert!(factorised(&[1.0 / 3.0, 1.0]).is_none());
+    }
+
+    #[test]
+    fn float_factors() {
+        // (X - 1)^2 (X + 2)^3 (X^2 + 1)
+        let p = Polynomial::from_coeffs(&[1.0, -1.0]).pow(2)
+            * Polynomial::from_coeffs(&[2.0, 1.0]).pow(3)
+            * Polynomial::from_coeffs(&[1.0, 0.0, 1.0]);
+        let factors = factorise_float(&p).unwrap().float_factors();
+        assert_eq!(factors.len(), 3);
+        assert!(factors[0].0 == Polynomial::from_coeffs(&[-1.0, 1.0]) && factors[0].1 == 2);
+        assert!(factors[1].0 == Polynomial::from_coeffs(&[2.0, 1.0]) && factors[1].1 == 3);
+        assert!(factors[2].0 == Polynomial::from_coeffs(&[1.0, 0.0, 1.0]) && factors[2].1 == 1);
+    }
+
+    #[test]
+    fn large_coefficients_are_not_factorised() {
+        let dense: Vec<f64> = (0..=40).map(|k| 1.234567 + k as f64).collect();
+        assert!(factorised(&dense).is_none());
+        let mut sparse = vec![0.0; 201];
+        sparse[0] = 1.0;
+        sparse[200] = 1.0;
+        assert!(factorised(&sparse).is_some());
+    }
+}
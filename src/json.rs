@@ -0,0 +1,161 @@
+use crate::lexer::LexicalError;
+use crate::maths::Scaled;
+use crate::parser::ParseError;
+use crate::solver::{RootKind, Roots, Solution, SolveError};
+
+fn number(value: f64) -> String {
+    match value.is_finite() {
+        true => format!("{}", value),
+        false => String::from("null"),
+    }
+}
+
+// A value too big for a f64 is still a valid JSON number once written with its exponent
+fn scaled(value: &Scaled) -> String {
+    match value.value.is_finite() {
+        true => value.to_string(),
+        false => String::from("null"),
+    }
+}
+
+fn string(value: &str) -> String {
+    let mut escaped = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+fn error(kind: &str, message: &str, index: Option<usize>) -> String {
+    format!(
+        "{{\"kind\":{},\"message\":{},\"index\":{}}}",
+        string(kind),
+        string(message),
+        match index {
+            Some(index) => index.to_string(),
+            None => String::from("null"),
+        }
+    )
+}
+
+fn errors(errors: Vec<String>) -> String {
+    format!(
+        "{{\"status\":\"error\",\"error\":{},\"errors\":[{}]}}",
+        errors.first().map_or("null", |err| err.as_str()),
+        errors.join(",")
+    )
+}
+
+fn parse_error(err: &ParseError) -> String {
+    error("parse", &err.to_string(), err.get_index())
+}
+
+pub fn lexical_errors(errs: &[LexicalError], parse_errs: &[ParseError]) -> String {
+    errors(
+        errs.iter()
+            .map(|err| error("lexical", &err.to_string(), Some(err.get_index())))
+            .chain(parse_errs.iter().map(parse_error))
+            .collect(),
+    )
+}
+
+pub fn parse_errors(errs: &[ParseError]) -> String {
+    errors(errs.iter().map(parse_error).collect())
+}
+
+pub fn solve_error(err: &SolveError) -> String {
+    errors(vec![error("solve", &err.to_string(), None)])
+}
+
+pub fn solution(solution: &Solution) -> String {
+    let reduced_form = (0..=solution.degree)
+        .map(|deg| {
+            format!(
+                "{{\"degree\":{},\"coefficient\":{}}}",
+                deg,
+                number(solution.polynomial.coeff(deg))
+            )
+        })
+        .collect::<Vec<String>>()
+        .join(",");
+    let (kind, roots) = match &solution.roots {
+        Roots::None => ("none", String::new()),
+        Roots::AllReals => ("all_reals", String::new()),
+        Roots::Roots(roots) => (
+            "roots",
+            roots
+                .iter()
+                .map(|root| {
+                    format!(
+                        "{{\"real\":{},\"imag\":{},\"multiplicity\":{},\"kind\":{}}}",
+                        number(root.value.real),
+                        number(root.value.imag),
+                        root.multiplicity,
+                        string(match root.kind {
+                            RootKind::Real => "real",
+                            RootKind::Complex => "complex",
+                        })
+                    )
+                })
+                .collect::<Vec<String>>()
+                .join(","),
+        ),
+    };
+    let rejected = solution
+        .rejected
+        .iter()
+        .map(|rejection| {
+            format!(
+                "{{\"real\":{},\"imag\":{},\"denominator\":{}}}",
+                number(rejection.value.real),
+                number(rejection.value.imag),
+                string(&rejection.denominator)
+            )
+        })
+        .collect::<Vec<String>>()
+        .join(",");
+    let factored_form = match &solution.factorisation {
+        Some(factorisation) => string(&format!(
+            "{} = 0",
+            factorisation.clone().named(solution.variable)
+        )),
+        None => String::from("null"),
+    };
+    let convergence = match &solution.convergence {
+        Some(convergence) => format!(
+            "{{\"iterations\":{},\"residuals\":[{}]}}",
+            convergence.iterations,
+            convergence
+                .residuals
+                .iter()
+                .map(|residual| number(*residual))
+                .collect::<Vec<String>>()
+                .join(",")
+        ),
+        None => String::from("null"),
+    };
+    format!(
+        "{{\"status\":\"ok\",\"variable\":{},\"reduced_form\":[{}],\"degree\":{},\"discriminant\":{},\"solutions\":{},\"roots\":[{}],\"rejected\":[{}],\"factored_form\":{},\"convergence\":{}}}",
+        string(&solution.variable.to_string()),
+        reduced_form,
+        solution.degree,
+        match solution.discriminant {
+            Some(discriminant) => scaled(&discriminant),
+            None => String::from("null"),
+        },
+        string(kind),
+        roots,
+        rejected,
+        factored_form,
+        convergence
+    )
+}
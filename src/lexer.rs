@@ -1,52 +1,187 @@
 use std::error::Error;
+use std::fmt;
 use std::mem;
-use std::{fmt, str};
+
+use num_traits::ToPrimitive;
+
+use crate::maths;
 
 pub struct Lexer {
     lexems: Vec<Lexem>,
 }
 
+impl Default for Lexer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Lexer {
-    const TRANSITIONS: [[usize; 11]; 14] = [
-        [0, 1, 2, 3, 4, 5, 6, 7, 13, 13, 12],
-        [1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1],
-        [2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2],
-        [3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3],
-        [4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4],
-        [5, 5, 5, 5, 5, 5, 5, 5, 5, 5, 5],
-        [6, 6, 6, 6, 6, 6, 6, 6, 6, 6, 6],
-        [11, 11, 11, 11, 11, 11, 11, 7, 8, 11, 11],
-        [13, 13, 13, 13, 13, 13, 13, 9, 13, 13, 13],
-        [10, 10, 10, 10, 10, 10, 10, 9, 10, 10, 10],
-        [10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10],
-        [11, 11, 11, 11, 11, 11, 11, 11, 11, 11, 11],
-        [12, 12, 12, 12, 12, 12, 12, 12, 12, 12, 12],
-        [13, 13, 13, 13, 13, 13, 13, 13, 13, 13, 13],
+    const TRANSITIONS: [[usize; 21]; 32] = [
+        [
+            0, 1, 2, 3, 4, 5, 6, 7, 18, 13, 12, 14, 15, 16, 17, 7, 13, 6, 6, 6, 6,
+        ],
+        [
+            1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
+        ],
+        [
+            2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2,
+        ],
+        [
+            3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3,
+        ],
+        [
+            4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4,
+        ],
+        [
+            5, 5, 5, 5, 5, 5, 5, 5, 5, 5, 5, 5, 5, 5, 5, 5, 5, 5, 5, 5, 5,
+        ],
+        [
+            6, 6, 6, 6, 6, 6, 6, 6, 6, 6, 6, 6, 6, 6, 6, 6, 6, 6, 6, 6, 6,
+        ],
+        [
+            11, 11, 11, 11, 11, 11, 11, 7, 8, 11, 11, 11, 11, 11, 7, 7, 19, 20, 11, 11, 11,
+        ],
+        [
+            10, 10, 10, 10, 10, 10, 10, 9, 10, 10, 10, 10, 10, 10, 9, 9, 10, 20, 10, 10, 10,
+        ],
+        [
+            10, 10, 10, 10, 10, 10, 10, 9, 10, 10, 10, 10, 10, 10, 9, 9, 21, 20, 10, 10, 10,
+        ],
+        [
+            10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10,
+        ],
+        [
+            11, 11, 11, 11, 11, 11, 11, 11, 11, 11, 11, 11, 11, 11, 11, 11, 11, 11, 11, 11, 11,
+        ],
+        [
+            12, 12, 12, 12, 12, 12, 12, 12, 12, 12, 12, 12, 12, 12, 12, 12, 12, 12, 12, 12, 12,
+        ],
+        [
+            13, 13, 13, 13, 13, 13, 13, 13, 13, 13, 13, 13, 13, 13, 13, 13, 13, 13, 13, 13, 13,
+        ],
+        [
+            14, 14, 14, 14, 14, 14, 14, 14, 14, 14, 14, 14, 14, 14, 14, 14, 14, 14, 14, 14, 14,
+        ],
+        [
+            15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15,
+        ],
+        [
+            16, 16, 16, 16, 16, 16, 16, 16, 16, 16, 16, 16, 16, 16, 16, 16, 16, 16, 16, 16, 16,
+        ],
+        [
+            11, 11, 11, 11, 11, 11, 11, 7, 8, 11, 11, 11, 11, 11, 7, 7, 19, 20, 23, 26, 11,
+        ],
+        [
+            13, 13, 13, 13, 13, 13, 13, 9, 13, 13, 13, 13, 13, 13, 9, 9, 13, 13, 13, 13, 13,
+        ],
+        [
+            30, 30, 30, 30, 30, 30, 30, 7, 30, 30, 30, 30, 30, 30, 7, 7, 30, 30, 30, 30, 30,
+        ],
+        [
+            30, 24, 24, 30, 30, 30, 30, 22, 30, 30, 30, 30, 30, 30, 22, 22, 30, 30, 30, 30, 30,
+        ],
+        [
+            30, 30, 30, 30, 30, 30, 30, 9, 30, 30, 30, 30, 30, 30, 9, 9, 30, 30, 30, 30, 30,
+        ],
+        [
+            10, 10, 10, 10, 10, 10, 10, 22, 10, 10, 10, 10, 10, 10, 22, 22, 10, 10, 10, 10, 10,
+        ],
+        [
+            30, 30, 30, 30, 30, 30, 30, 27, 30, 30, 30, 30, 30, 30, 27, 27, 30, 27, 30, 27, 27,
+        ],
+        [
+            31, 31, 31, 31, 31, 31, 31, 22, 31, 31, 31, 31, 31, 31, 22, 22, 31, 31, 31, 31, 31,
+        ],
+        [
+            30, 30, 30, 30, 30, 30, 30, 27, 30, 30, 30, 30, 30, 30, 27, 27, 30, 27, 30, 27, 27,
+        ],
+        [
+            30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 29, 29, 30, 30, 30, 30, 30,
+        ],
+        [
+            10, 10, 10, 10, 10, 10, 10, 27, 10, 10, 10, 10, 10, 10, 27, 27, 25, 27, 10, 27, 27,
+        ],
+        [
+            30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 29, 29, 30, 30, 30, 30, 30,
+        ],
+        [
+            10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 29, 29, 28, 10, 10, 10, 10,
+        ],
+        [
+            30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30,
+        ],
+        [
+            31, 31, 31, 31, 31, 31, 31, 31, 31, 31, 31, 31, 31, 31, 31, 31, 31, 31, 31, 31, 31,
+        ],
     ];
 
-    const STATES: [State; 14] = [
+    const STATES: [State; 32] = [
         State::Initial,
         State::Final(Lexem::Plus { index: 0, len: 0 }),
         State::Final(Lexem::Minus { index: 0, len: 0 }),
         State::Final(Lexem::Mult { index: 0, len: 0 }),
         State::Final(Lexem::Power { index: 0, len: 0 }),
         State::Final(Lexem::Equal { index: 0, len: 0 }),
-        State::Final(Lexem::X { index: 0, len: 0 }),
-        State::Transitory,
-        State::Transitory,
-        State::Transitory,
-        State::FinalStar(Lexem::Number {
-            value: 0.0,
-            index: 0,
-            len: 0,
-        }),
-        State::FinalStar(Lexem::Number {
-            value: 0.0,
+        State::Final(Lexem::Identifier {
+            name: 'X',
             index: 0,
             len: 0,
         }),
+        State::Transitory,
+        State::Transitory,
+        State::Transitory,
+        State::FinalStar(
+            Lexem::Number {
+                value: 0.0,
+                index: 0,
+                len: 0,
+            },
+            1,
+        ),
+        State::FinalStar(
+            Lexem::Number {
+                value: 0.0,
+                index: 0,
+                len: 0,
+            },
+            1,
+        ),
         State::Final(Lexem::End { index: 0, len: 0 }),
         State::Error,
+        State::Final(Lexem::OpenParenthesis { index: 0, len: 0 }),
+        State::Final(Lexem::CloseParenthesis { index: 0, len: 0 }),
+        State::Final(Lexem::Div { index: 0, len: 0 }),
+        State::Transitory,
+        State::Transitory,
+        State::Transitory,
+        State::Transitory,
+        State::Transitory,
+        State::Transitory,
+        State::Transitory,
+        State::Transitory,
+        State::Transitory,
+        State::Transitory,
+        State::Transitory,
+        State::Transitory,
+        State::Transitory,
+        // A number followed by `_`, `e`, `e-`, `0x` or `0b` without any digit ends before them
+        State::FinalStar(
+            Lexem::Number {
+                value: 0.0,
+                index: 0,
+                len: 0,
+            },
+            2,
+        ),
+        State::FinalStar(
+            Lexem::Number {
+                value: 0.0,
+                index: 0,
+                len: 0,
+            },
+            3,
+        ),
     ];
 
     pub fn new() -> Self {
@@ -59,9 +194,22 @@ impl Lexer {
         start: usize,
         end: usize,
     ) -> Result<(), LexicalError> {
-        let nb_str: &str = str::from_utf8(&(equation.as_bytes())[start..end]).unwrap();
-        match nb_str.parse::<f64>() {
-            Ok(nb) => {
+        let nb_str: &str = &equation[start..end];
+        let value = match maths::radix(nb_str) {
+            Some(_) => maths::parse_literal(nb_str).and_then(|nb| nb.to_f64()),
+            None => nb_str.replace('_', "").parse::<f64>().ok(),
+        };
+        // A nonzero decimal literal rounded to zero or to a subnormal has lost its digits
+        let underflows = |nb: f64| {
+            !nb.is_normal()
+                && maths::radix(nb_str).is_none()
+                && nb_str
+                    .split(['e', 'E'])
+                    .next()
+                    .is_some_and(|mantissa| mantissa.contains(|c: char| ('1'..='9').contains(&c)))
+        };
+        match value {
+            Some(nb) if nb.is_finite() && !underflows(nb) => {
                 self.lexems.push(Lexem::Number {
                     value: nb,
                     index: start,
@@ -69,10 +217,26 @@ impl Lexer {
                 });
                 Ok(())
             }
-            Err(_) => return Err(LexicalError::TooBigNumber(String::from(nb_str), start)),
+            Some(nb) if nb.is_finite() => {
+                self.add_placeholder(start, end);
+                Err(LexicalError::TooSmallNumber(String::from(nb_str), start))
+            }
+            _ => {
+                self.add_placeholder(start, end);
+                Err(LexicalError::TooBigNumber(String::from(nb_str), start))
+            }
         }
     }
 
+    // Stands for an invalid number or character, so that the parser can look for more errors
+    fn add_placeholder(&mut self, start: usize, end: usize) {
+        self.lexems.push(Lexem::Number {
+            value: 0.0,
+            index: start,
+            len: end - start,
+        });
+    }
+
     fn add_lexem(
         &mut self,
         lexem_type: &Lexem,
@@ -99,6 +263,10 @@ impl Lexer {
                 index: start,
                 len: _len,
             }),
+            Lexem::Div { index: _, len: _ } => self.lexems.push(Lexem::Div {
+                index: start,
+                len: _len,
+            }),
             Lexem::Equal { index: _, len: _ } => self.lexems.push(Lexem::Equal {
                 index: start,
                 len: _len,
@@ -107,10 +275,27 @@ impl Lexer {
                 index: start,
                 len: _len,
             }),
-            Lexem::X { index: _, len: _ } => self.lexems.push(Lexem::X {
+            Lexem::Identifier {
+                name: _,
+                index: _,
+                len: _,
+            } => self.lexems.push(Lexem::Identifier {
+                name: equation[start..end].chars().next().unwrap_or('X'),
                 index: start,
                 len: _len,
             }),
+            Lexem::OpenParenthesis { index: _, len: _ } => {
+                self.lexems.push(Lexem::OpenParenthesis {
+                    index: start,
+                    len: _len,
+                })
+            }
+            Lexem::CloseParenthesis { index: _, len: _ } => {
+                self.lexems.push(Lexem::CloseParenthesis {
+                    index: start,
+                    len: _len,
+                })
+            }
             Lexem::End { index: _, len: _ } => self.lexems.push(Lexem::End {
                 index: start,
                 len: _len,
@@ -127,49 +312,77 @@ impl Lexer {
             '*' => 3,
             '^' => 4,
             '=' => 5,
-            'X' => 6,
+            '0' => 14,
+            '1' => 15,
+            '_' => 16,
+            'e' | 'E' => 17,
+            'x' => 18,
+            'b' => 19,
+            val if val.is_ascii_hexdigit() && val.is_ascii_alphabetic() => 20,
+            val if val.is_ascii_alphabetic() => 6,
             val if val.is_numeric() => 7,
             '.' => 8,
             val if val == 0x0 as char => 10,
+            '(' => 11,
+            ')' => 12,
+            '/' => 13,
             _ => 9,
         }
     }
 
-    pub fn run(&mut self, equation: &str) -> Result<(), LexicalError> {
+    pub fn run(&mut self, equation: &str) -> Result<(), Vec<LexicalError>> {
         let mut bytes: Vec<u8> = equation.as_bytes().to_vec();
         bytes.push(0x0);
         let len: usize = bytes.len();
         let mut cursor: usize = 0;
         let mut state: usize = 0;
         let mut lexem_start: usize = 0;
+        let mut errors: Vec<LexicalError> = Vec::new();
 
         self.lexems = Vec::new();
         while cursor < len {
-            state = Lexer::TRANSITIONS[state][self.get_state_machine_col(bytes[cursor] as char)];
+            let col = self.get_state_machine_col(bytes[cursor] as char);
+            state = Lexer::TRANSITIONS[state][col];
             match &Lexer::STATES[state] {
                 State::Initial => lexem_start = cursor + 1,
                 State::Transitory => (),
                 State::Final(lexem_type) => {
-                    self.add_lexem(lexem_type, equation, lexem_start, cursor + 1)?;
+                    if let Err(err) = self.add_lexem(lexem_type, equation, lexem_start, cursor + 1)
+                    {
+                        errors.push(err);
+                    }
                     lexem_start = cursor + 1;
                     state = 0;
                 }
-                State::FinalStar(lexem_type) => {
-                    self.add_lexem(lexem_type, equation, lexem_start, cursor)?;
-                    lexem_start = cursor;
+                State::FinalStar(lexem_type, back) => {
+                    let end = cursor + 1 - back;
+                    if let Err(err) = self.add_lexem(lexem_type, equation, lexem_start, end) {
+                        errors.push(err);
+                    }
+                    lexem_start = end;
                     state = 0;
-                    cursor -= 1;
+                    cursor = end;
+                    continue;
                 }
                 State::Error => {
-                    return Err(LexicalError::UnexpectedCharacter(
-                        bytes[cursor] as char,
-                        cursor,
-                    ))
+                    let c = equation
+                        .get(lexem_start..)
+                        .and_then(|rest| rest.chars().next())
+                        .unwrap_or(bytes[lexem_start] as char);
+                    errors.push(LexicalError::UnexpectedCharacter(c, lexem_start));
+                    self.add_placeholder(lexem_start, lexem_start + c.len_utf8());
+                    state = 0;
+                    cursor = lexem_start + c.len_utf8();
+                    lexem_start = cursor;
+                    continue;
                 }
             }
             cursor += 1;
         }
-        Ok(())
+        match errors.is_empty() {
+            true => Ok(()),
+            false => Err(errors),
+        }
     }
 
     pub fn get_lexems(&self) -> &Vec<Lexem> {
@@ -180,6 +393,25 @@ impl Lexer {
 pub enum LexicalError {
     UnexpectedCharacter(char, usize),
     TooBigNumber(String, usize),
+    TooSmallNumber(String, usize),
+}
+
+impl LexicalError {
+    pub fn get_index(&self) -> usize {
+        match self {
+            LexicalError::UnexpectedCharacter(_, pos) => *pos,
+            LexicalError::TooBigNumber(_, pos) => *pos,
+            LexicalError::TooSmallNumber(_, pos) => *pos,
+        }
+    }
+
+    pub fn get_span(&self) -> (usize, usize) {
+        match self {
+            LexicalError::UnexpectedCharacter(_, pos) => (*pos, 1),
+            LexicalError::TooBigNumber(number, pos) => (*pos, number.len()),
+            LexicalError::TooSmallNumber(number, pos) => (*pos, number.len()),
+        }
+    }
 }
 
 impl fmt::Display for LexicalError {
@@ -191,11 +423,14 @@ impl fmt::Display for LexicalError {
             LexicalError::TooBigNumber(number, pos) => {
                 write!(f, "'{}' (at index {}) is a too big number", number, pos)
             }
+            LexicalError::TooSmallNumber(number, pos) => {
+                write!(f, "'{}' (at index {}) is a too small number", number, pos)
+            }
         }
     }
 }
 
-impl<'a> fmt::Debug for LexicalError {
+impl fmt::Debug for LexicalError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "LexicalError: {}", self)
     }
@@ -217,6 +452,10 @@ pub enum Lexem {
         index: usize,
         len: usize,
     },
+    Div {
+        index: usize,
+        len: usize,
+    },
     Power {
         index: usize,
         len: usize,
@@ -225,7 +464,16 @@ pub enum Lexem {
         index: usize,
         len: usize,
     },
-    X {
+    Identifier {
+        name: char,
+        index: usize,
+        len: usize,
+    },
+    OpenParenthesis {
+        index: usize,
+        len: usize,
+    },
+    CloseParenthesis {
         index: usize,
         len: usize,
     },
@@ -251,13 +499,55 @@ impl Lexem {
             Lexem::Plus { index, len: _ } => *index,
             Lexem::Minus { index, len: _ } => *index,
             Lexem::Mult { index, len: _ } => *index,
+            Lexem::Div { index, len: _ } => *index,
             Lexem::Power { index, len: _ } => *index,
             Lexem::Equal { index, len: _ } => *index,
-            Lexem::X { index, len: _ } => *index,
+            Lexem::Identifier {
+                name: _,
+                index,
+                len: _,
+            } => *index,
+            Lexem::OpenParenthesis { index, len: _ } => *index,
+            Lexem::CloseParenthesis { index, len: _ } => *index,
             Lexem::End { index, len: _ } => *index,
         }
     }
 
+    pub fn get_len(&self) -> usize {
+        match self {
+            Lexem::Number {
+                value: _,
+                index: _,
+                len,
+            } => *len,
+            Lexem::Plus { index: _, len } => *len,
+            Lexem::Minus { index: _, len } => *len,
+            Lexem::Mult { index: _, len } => *len,
+            Lexem::Div { index: _, len } => *len,
+            Lexem::Power { index: _, len } => *len,
+            Lexem::Equal { index: _, len } => *len,
+            Lexem::Identifier {
+                name: _,
+                index: _,
+                len,
+            } => *len,
+            Lexem::OpenParenthesis { index: _, len } => *len,
+            Lexem::CloseParenthesis { index: _, len } => *len,
+            Lexem::End { index: _, len } => *len,
+        }
+    }
+
+    pub fn get_name(&self) -> char {
+        match self {
+            Lexem::Identifier {
+                name,
+                index: _,
+                len: _,
+            } => *name,
+            _ => 'X',
+        }
+    }
+
     pub fn get_value(&self) -> f64 {
         match self {
             Lexem::Number {
@@ -280,7 +570,7 @@ enum State {
     Initial,
     Transitory,
     Final(Lexem),
-    FinalStar(Lexem),
+    FinalStar(Lexem, usize),
     Error,
 }
 
@@ -290,9 +580,16 @@ impl fmt::Debug for Lexem {
             Lexem::Plus { index: _, len: _ } => write!(f, "+"),
             Lexem::Minus { index: _, len: _ } => write!(f, "-"),
             Lexem::Mult { index: _, len: _ } => write!(f, "*"),
+            Lexem::Div { index: _, len: _ } => write!(f, "/"),
             Lexem::Power { index: _, len: _ } => write!(f, "^"),
             Lexem::Equal { index: _, len: _ } => write!(f, "="),
-            Lexem::X { index: _, len: _ } => write!(f, "X"),
+            Lexem::Identifier {
+                name,
+                index: _,
+                len: _,
+            } => write!(f, "{}", name),
+            Lexem::OpenParenthesis { index: _, len: _ } => write!(f, "("),
+            Lexem::CloseParenthesis { index: _, len: _ } => write!(f, ")"),
             Lexem::Number {
                 value,
                 index: _,
@@ -302,3 +599,37 @@ impl fmt::Debug for Lexem {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lex(equation: &str) -> String {
+        let mut lexer = Lexer::new();
+        lexer.run(equation).unwrap();
+        format!("{:?}", lexer.get_lexems())
+    }
+
+    #[test]
+    fn any_letter_is_an_identifier() {
+        assert_eq!(lex("t^2 = x"), "[t, ^, 2, =, x, END]");
+        assert_eq!(lex("2e3 = 2e - 3"), "[2000, =, 2, e, -, 3, END]");
+        assert_eq!(lex("0x1 = 0x + 1"), "[1, =, 0, x, +, 1, END]");
+    }
+
+    #[test]
+    fn out_of_range_numbers() {
+        let mut lexer = Lexer::new();
+        let errs = lexer.run("1e-400 X = 1e999").err().unwrap();
+        assert_eq!(errs.len(), 2);
+        assert!(matches!(&errs[0], LexicalError::TooSmallNumber(number, 0) if number == "1e-400"));
+        assert!(matches!(&errs[1], LexicalError::TooBigNumber(number, 11) if number == "1e999"));
+        assert_eq!(format!("{:?}", lexer.get_lexems()), "[0, X, =, 0, END]");
+        assert!(matches!(
+            Lexer::new().run("1e-310 = 0").err().unwrap()[0],
+            LexicalError::TooSmallNumber(_, 0)
+        ));
+        assert_eq!(lex("0e-400 + 0.0 = 1e-5"), "[0, +, 0, =, 0.00001, END]");
+        assert_eq!(lex("0x0 = 0b0"), "[0, =, 0, END]");
+    }
+}
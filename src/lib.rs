@@ -1,5 +1,13 @@
 pub mod args;
+pub mod ast;
+pub mod batch;
+pub mod diagnostics;
+pub mod exact;
+pub mod factor;
+pub mod json;
 pub mod lexer;
 pub mod maths;
 pub mod parser;
+pub mod pipeline;
+pub mod repl;
 pub mod solver;
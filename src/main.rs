@@ -1,21 +1,44 @@
 use std::error::Error;
+use std::fs::File;
+use std::io;
+use std::io::BufReader;
+use std::process;
 use structopt::StructOpt;
 
 use computor_v1::args;
-use computor_v1::lexer::Lexer;
-use computor_v1::parser::Parser;
-use computor_v1::solver;
+use computor_v1::batch;
+use computor_v1::pipeline;
+use computor_v1::pipeline::Settings;
+use computor_v1::repl;
 
 fn main() -> Result<(), Box<dyn Error>> {
     let input = args::UserInput::from_args();
-    let mut lexer = Lexer::new();
-    lexer.run(&input.equation)?;
-    let lexems = lexer.get_lexems();
-    let mut parser = Parser::new();
-    parser.run(lexems)?;
-    let degrees = parser.get_degrees();
-    let mut solver = solver::choose_solver(degrees);
-    solver.solve(degrees);
-    solver.describe();
+    let settings = Settings {
+        format: input.format,
+        exact: input.exact,
+        epsilon: input.epsilon,
+        variable: input.var,
+        strict: input.strict,
+        ..Settings::default()
+    };
+    if let Some(path) = input.batch {
+        let summary = match path.to_str() {
+            Some("-") => batch::run(io::stdin().lock(), &settings)?,
+            _ => batch::run(BufReader::new(File::open(&path)?), &settings)?,
+        };
+        if summary.errors > 0 {
+            process::exit(1);
+        }
+        return Ok(());
+    }
+    match input.equation {
+        Some(equation) if !input.repl => {
+            if let Err(err) = pipeline::run(&equation, &settings) {
+                pipeline::report_error(&equation, &err, &settings);
+                process::exit(1);
+            }
+        }
+        _ => repl::run(settings)?,
+    }
     Ok(())
 }
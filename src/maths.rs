@@ -1,13 +1,1224 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_rational::BigRational;
+use num_traits::{FromPrimitive, One, Signed, ToPrimitive, Zero};
+
+#[derive(Copy, Clone)]
 pub struct Complex {
     pub real: f64,
     pub imag: f64,
 }
 
-pub fn sqrt(number: f64) -> f64 {
-    let threshold: f64 = 0.000001;
-    let mut ans: f64 = number / 2.0;
-    while ((ans * ans) - number).abs() > threshold {
-        ans = (ans + (number / ans)) / 2.0;
+impl Complex {
+    pub fn new(real: f64, imag: f64) -> Self {
+        Complex { real, imag }
+    }
+
+    pub fn from_polar(modulus: f64, arg: f64) -> Self {
+        Complex {
+            real: modulus * arg.cos(),
+            imag: modulus * arg.sin(),
+        }
+    }
+
+    pub fn to_polar(&self) -> (f64, f64) {
+        (self.abs(), self.arg())
+    }
+
+    pub fn abs(&self) -> f64 {
+        self.real.hypot(self.imag)
+    }
+
+    pub fn arg(&self) -> f64 {
+        self.imag.atan2(self.real)
+    }
+
+    pub fn conj(&self) -> Self {
+        Complex {
+            real: self.real,
+            imag: -self.imag,
+        }
+    }
+
+    pub fn sqrt(&self) -> Self {
+        csqrt(*self)
+    }
+
+    pub fn powi(&self, exponent: i32) -> Self {
+        let mut result = Complex::new(1.0, 0.0);
+        let mut base = *self;
+        let mut n = exponent.unsigned_abs();
+        while n > 0 {
+            if n % 2 == 1 {
+                result = result * base;
+            }
+            n /= 2;
+            if n > 0 {
+                base = base * base;
+            }
+        }
+        match exponent < 0 {
+            true => Complex::new(1.0, 0.0) / result,
+            false => result,
+        }
+    }
+
+    pub fn exp(&self) -> Self {
+        Complex::from_polar(self.real.exp(), self.imag)
+    }
+
+    pub fn approx_eq(&self, other: &Complex, epsilon: f64) -> bool {
+        let scale = 1.0_f64.max(self.abs()).max(other.abs());
+        (*self - *other).abs() <= epsilon * scale
+    }
+}
+
+impl Add for Complex {
+    type Output = Complex;
+
+    fn add(self, other: Complex) -> Complex {
+        Complex::new(self.real + other.real, self.imag + other.imag)
+    }
+}
+
+impl Sub for Complex {
+    type Output = Complex;
+
+    fn sub(self, other: Complex) -> Complex {
+        Complex::new(self.real - other.real, self.imag - other.imag)
+    }
+}
+
+impl Mul for Complex {
+    type Output = Complex;
+
+    fn mul(self, other: Complex) -> Complex {
+        Complex::new(
+            self.real * other.real - self.imag * other.imag,
+            self.real * other.imag + self.imag * other.real,
+        )
+    }
+}
+
+impl Div for Complex {
+    type Output = Complex;
+
+    // Smith's algorithm: the ratio of the divisor's components never overflows, unlike its norm
+    fn div(self, other: Complex) -> Complex {
+        if other.real.abs() >= other.imag.abs() {
+            let ratio = other.imag / other.real;
+            let denominator = other.real + other.imag * ratio;
+            Complex::new(
+                (self.real + self.imag * ratio) / denominator,
+                (self.imag - self.real * ratio) / denominator,
+            )
+        } else {
+            let ratio = other.real / other.imag;
+            let denominator = other.real * ratio + other.imag;
+            Complex::new(
+                (self.real * ratio + self.imag) / denominator,
+                (self.imag * ratio - self.real) / denominator,
+            )
+        }
+    }
+}
+
+impl Neg for Complex {
+    type Output = Complex;
+
+    fn neg(self) -> Complex {
+        Complex::new(-self.real, -self.imag)
+    }
+}
+
+impl Add<f64> for Complex {
+    type Output = Complex;
+
+    fn add(self, other: f64) -> Complex {
+        Complex::new(self.real + other, self.imag)
+    }
+}
+
+impl Sub<f64> for Complex {
+    type Output = Complex;
+
+    fn sub(self, other: f64) -> Complex {
+        Complex::new(self.real - other, self.imag)
+    }
+}
+
+impl Mul<f64> for Complex {
+    type Output = Complex;
+
+    fn mul(self, other: f64) -> Complex {
+        Complex::new(self.real * other, self.imag * other)
+    }
+}
+
+impl Div<f64> for Complex {
+    type Output = Complex;
+
+    fn div(self, other: f64) -> Complex {
+        Complex::new(self.real / other, self.imag / other)
+    }
+}
+
+impl Add<Complex> for f64 {
+    type Output = Complex;
+
+    fn add(self, other: Complex) -> Complex {
+        other + self
+    }
+}
+
+impl Sub<Complex> for f64 {
+    type Output = Complex;
+
+    fn sub(self, other: Complex) -> Complex {
+        Complex::new(self - other.real, -other.imag)
+    }
+}
+
+impl Mul<Complex> for f64 {
+    type Output = Complex;
+
+    fn mul(self, other: Complex) -> Complex {
+        other * self
+    }
+}
+
+impl Div<Complex> for f64 {
+    type Output = Complex;
+
+    fn div(self, other: Complex) -> Complex {
+        Complex::new(self, 0.0) / other
+    }
+}
+
+impl fmt::Display for Complex {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let precision = f.precision().unwrap_or(2);
+        let mut real = format!("{:.*}", precision, self.real);
+        if real.parse::<f64>() == Ok(0.0) {
+            real = real.trim_start_matches('-').to_string();
+        }
+        let imag = format!("{:.*}", precision, self.imag.abs());
+        if imag.parse::<f64>() == Ok(0.0) {
+            write!(f, "{}", real)
+        } else {
+            write!(
+                f,
+                "{} {} {} * i",
+                real,
+                if self.imag >= 0.0 { "+" } else { "-" },
+                imag
+            )
+        }
+    }
+}
+
+const MANTISSA_BITS: u64 = 52;
+const EXPONENT_MASK: u64 = 0x7ff;
+const EXPONENT_BIAS: i64 = 1023;
+
+fn split(number: f64) -> (f64, i64) {
+    let bits = number.to_bits();
+    let exponent = ((bits >> MANTISSA_BITS) & EXPONENT_MASK) as i64;
+    if exponent == 0 {
+        // subnormal: scale it up by 2^54 so that its mantissa is normalised
+        let (mantissa, exponent) = split(number * power_of_two(54));
+        return (mantissa, exponent - 54);
+    }
+    let mantissa_mask = (1 << MANTISSA_BITS) - 1;
+    let mantissa =
+        f64::from_bits((bits & mantissa_mask) | ((EXPONENT_BIAS as u64) << MANTISSA_BITS));
+    (mantissa, exponent - EXPONENT_BIAS)
+}
+
+pub fn power_of_two(exponent: i64) -> f64 {
+    f64::from_bits(((exponent + EXPONENT_BIAS) as u64) << MANTISSA_BITS)
+}
+
+pub fn sqrt(number: f64) -> Option<f64> {
+    if number.is_nan() || number == 0.0 || number == f64::INFINITY {
+        return Some(number);
+    }
+    if number < 0.0 {
+        return None;
+    }
+    // number = mantissa * 2^exponent, with mantissa in [1, 4) and an even exponent
+    let (mut mantissa, mut exponent) = split(number);
+    if exponent % 2 != 0 {
+        mantissa *= 2.0;
+        exponent -= 1;
+    }
+    let threshold: f64 = f64::EPSILON;
+    let mut ans: f64 = (1.0 + mantissa) / 2.0;
+    loop {
+        let next: f64 = (ans + mantissa / ans) / 2.0;
+        if (ans - next).abs() <= threshold * next {
+            ans = next;
+            break;
+        }
+        ans = next;
+    }
+    // ans is within an ulp: with the mantissas as integers, round (A ± 1/2)² against M exactly
+    let scale = power_of_two(MANTISSA_BITS as i64);
+    let target = ((mantissa * scale) as u128) << (MANTISSA_BITS + 2);
+    let mut root = (ans * scale) as u128;
+    while (2 * root + 1).pow(2) < target {
+        root += 1;
+    }
+    while (2 * root - 1).pow(2) > target {
+        root -= 1;
+    }
+    Some(root as f64 / scale * power_of_two(exponent / 2))
+}
+
+pub fn csqrt(z: Complex) -> Complex {
+    if z.imag.is_infinite() {
+        return Complex::new(f64::INFINITY, z.imag);
+    }
+    if z.real.is_nan() || z.imag.is_nan() {
+        return Complex::new(f64::NAN, f64::NAN);
+    }
+    if z.imag == 0.0 {
+        return match sqrt(z.real) {
+            Some(root) => Complex::new(root, z.imag),
+            None => Complex::new(0.0, sqrt(-z.real).unwrap_or(f64::NAN).copysign(z.imag)),
+        };
+    }
+    if z.real.abs() > f64::MAX / 4.0 || z.imag.abs() > f64::MAX / 4.0 {
+        return csqrt(z / 4.0) * 2.0;
+    }
+    let t = sqrt(z.real.abs() / 2.0 + z.abs() / 2.0).unwrap_or(f64::NAN);
+    match z.real >= 0.0 {
+        true => Complex::new(t, z.imag / (2.0 * t)),
+        false => Complex::new(z.imag.abs() / (2.0 * t), t.copysign(z.imag)),
+    }
+}
+
+pub fn cbrt(number: f64) -> f64 {
+    if number.is_nan() || number.is_infinite() || number == 0.0 {
+        return number;
+    }
+    let threshold: f64 = 0.000000001;
+    let abs: f64 = number.abs();
+    let mut ans: f64 = if abs > 1.0 { abs / 3.0 } else { 1.0 };
+    loop {
+        let next: f64 = (2.0 * ans + abs / (ans * ans)) / 3.0;
+        if (next - ans).abs() <= threshold * next {
+            ans = next;
+            break;
+        }
+        ans = next;
+    }
+    if number < 0.0 {
+        -ans
+    } else {
+        ans
+    }
+}
+
+pub fn binary_exponent(numbers: &[f64]) -> i64 {
+    let largest = numbers
+        .iter()
+        .fold(0.0, |acc: f64, number| number.abs().max(acc));
+    if largest == 0.0 || !largest.is_finite() {
+        return 0;
+    }
+    let (_, exponent) = split(largest);
+    exponent.max(1 - EXPONENT_BIAS)
+}
+
+// numerator / denominator * 2^exponent, without overflowing on the way
+pub fn scaled_ratio(numerator: f64, denominator: f64, exponent: i64) -> f64 {
+    if numerator == 0.0 || !numerator.is_finite() || !denominator.is_normal() {
+        return numerator / denominator * power_of_two(exponent.clamp(-1022, 1023));
+    }
+    let (numerator_mantissa, numerator_exponent) = split(numerator);
+    let (denominator_mantissa, denominator_exponent) = split(denominator);
+    let sign = numerator.signum() * denominator.signum();
+    Scaled::new(
+        sign * numerator_mantissa / denominator_mantissa,
+        numerator_exponent - denominator_exponent + exponent,
+    )
+    .to_f64()
+}
+
+pub fn binary_scale(numbers: &[f64]) -> f64 {
+    power_of_two(binary_exponent(numbers))
+}
+
+// value * 2^exponent, for the results of scaled coefficients that may not fit in a f64
+#[derive(Copy, Clone)]
+pub struct Scaled {
+    pub value: f64,
+    pub exponent: i64,
+}
+
+impl Scaled {
+    pub fn new(value: f64, exponent: i64) -> Self {
+        Scaled { value, exponent }
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        let (mut value, mut exponent) = (self.value, self.exponent);
+        while exponent != 0 {
+            let step = exponent.clamp(-1000, 1000);
+            value *= power_of_two(step);
+            exponent -= step;
+        }
+        value
+    }
+}
+
+impl fmt::Display for Scaled {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let value = self.to_f64();
+        if !self.value.is_finite() || (value.is_finite() && (value != 0.0 || self.value == 0.0)) {
+            return write!(f, "{}", value);
+        }
+        let log = self.value.abs().log10() + self.exponent as f64 * 2.0_f64.log10();
+        let mantissa = format!("{:.10}", 10.0_f64.powf(log - log.floor()));
+        write!(
+            f,
+            "{}{}e{}",
+            if self.value < 0.0 { "-" } else { "" },
+            mantissa.trim_end_matches('0').trim_end_matches('.'),
+            log.floor()
+        )
+    }
+}
+
+pub fn chop(value: f64, terms: &[f64], epsilon: f64) -> f64 {
+    let scale = terms.iter().fold(0.0, |acc: f64, term| term.abs().max(acc));
+    // An overflow is not a zero, even next to infinite terms
+    match value.is_finite() && value.abs() <= epsilon * scale {
+        true => 0.0,
+        false => value,
+    }
+}
+
+const SQUARE_FACTOR_BOUND: u32 = 10_000;
+const EXPONENT_BOUND: u64 = 1_000;
+
+pub fn radix(text: &str) -> Option<(&str, u32)> {
+    match text.get(..2) {
+        Some("0x") => Some((&text[2..], 16)),
+        Some("0b") => Some((&text[2..], 2)),
+        _ => None,
+    }
+}
+
+pub fn parse_literal(text: &str) -> Option<BigRational> {
+    let text = text.replace('_', "");
+    if let Some((digits, radix)) = radix(&text) {
+        return Some(BigRational::from_integer(BigInt::parse_bytes(
+            digits.as_bytes(),
+            radix,
+        )?));
+    }
+    let (mantissa, exponent) = match text.find(['e', 'E']) {
+        Some(e) => (&text[..e], text[e + 1..].parse::<i64>().ok()?),
+        None => (text.as_str(), 0),
+    };
+    let (integer, fraction) = match mantissa.find('.') {
+        Some(dot) => (&mantissa[..dot], &mantissa[dot + 1..]),
+        None => (mantissa, ""),
+    };
+    let exponent = exponent - fraction.len() as i64;
+    if exponent.unsigned_abs() > EXPONENT_BOUND {
+        return None;
+    }
+    let numerator = BigInt::parse_bytes(format!("{}{}", integer, fraction).as_bytes(), 10)?;
+    let scale = num_traits::pow(BigInt::from(10), exponent.unsigned_abs() as usize);
+    match exponent < 0 {
+        true => Some(BigRational::new(numerator, scale)),
+        false => Some(BigRational::from_integer(numerator * scale)),
+    }
+}
+
+pub fn to_rational(number: f64) -> Option<BigRational> {
+    let text = format!("{}", number);
+    let digits = text
+        .trim_start_matches('-')
+        .replace('.', "")
+        .trim_matches('0')
+        .len();
+    match number.is_finite() && digits <= 15 {
+        true => parse_literal(&text),
+        false => None,
+    }
+}
+
+pub struct Surd {
+    rational: BigRational,
+    coeff: BigRational,
+    radicand: BigInt,
+    imaginary: bool,
+}
+
+impl Surd {
+    pub fn new(rational: BigRational, coeff: BigRational, radicand: BigInt) -> Self {
+        let imaginary = radicand.is_negative();
+        let mut radicand = radicand.abs();
+        let mut factor = BigInt::one();
+        let mut k = BigInt::from(2);
+        while k <= BigInt::from(SQUARE_FACTOR_BOUND) && &k * &k <= radicand {
+            let square = &k * &k;
+            while (&radicand % &square).is_zero() {
+                radicand /= &square;
+                factor *= &k;
+            }
+            k += 1;
+        }
+        let root = radicand.sqrt();
+        if &root * &root == radicand {
+            factor *= root;
+            radicand = BigInt::one();
+        }
+        let coeff = coeff * BigRational::from_integer(factor);
+        match (imaginary, radicand.is_one()) {
+            (false, true) => Surd {
+                rational: rational + coeff,
+                coeff: BigRational::zero(),
+                radicand: BigInt::zero(),
+                imaginary,
+            },
+            _ => Surd {
+                rational,
+                coeff,
+                radicand,
+                imaginary,
+            },
+        }
+    }
+
+    pub fn is_rational(&self) -> bool {
+        self.coeff.is_zero() || self.radicand.is_zero()
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.is_rational() && self.rational.is_integer()
+    }
+
+    pub fn to_complex(&self) -> Complex {
+        let rational = self.rational.to_f64().unwrap_or(f64::NAN);
+        // Above 2^104 the integer square root is exact to the f64 precision, and cannot overflow
+        let root = match self.radicand.bits() > 104 {
+            true => self.radicand.sqrt().to_f64(),
+            false => self.radicand.to_f64().and_then(sqrt),
+        };
+        let radical = self.coeff.to_f64().unwrap_or(f64::NAN) * root.unwrap_or(f64::NAN);
+        match self.imaginary {
+            true => Complex {
+                real: rational,
+                imag: radical,
+            },
+            // a + b√c = (a^2 - b^2c) / (a - b√c) avoids the cancellation when a and b√c are close
+            false if rational * radical < 0.0 => {
+                let numerator = &self.rational * &self.rational
+                    - &self.coeff * &self.coeff * BigRational::from_integer(self.radicand.clone());
+                Complex {
+                    real: numerator.to_f64().unwrap_or(f64::NAN) / (rational - radical),
+                    imag: 0.0,
+                }
+            }
+            false => Complex {
+                real: rational + radical,
+                imag: 0.0,
+            },
+        }
+    }
+}
+
+impl fmt::Display for Surd {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_rational() {
+            return write!(f, "{}", self.rational);
+        }
+        let denom = self.rational.denom().lcm(self.coeff.denom());
+        let a = (&self.rational * BigRational::from_integer(denom.clone())).to_integer();
+        let b = (&self.coeff * BigRational::from_integer(denom.clone())).to_integer();
+        let unit = match (self.imaginary, self.radicand.is_one()) {
+            (true, true) => String::from("i"),
+            (true, false) => format!("i√{}", self.radicand),
+            (false, _) => format!("√{}", self.radicand),
+        };
+        let radical = match b.abs().is_one() {
+            true => unit,
+            false => format!("{}{}", b.abs(), unit),
+        };
+        let numerator = match (a.is_zero(), b.is_negative()) {
+            (true, true) => format!("-{}", radical),
+            (true, false) => radical,
+            (false, true) => format!("{} - {}", a, radical),
+            (false, false) => format!("{} + {}", a, radical),
+        };
+        match (denom.is_one(), a.is_zero()) {
+            (true, _) => write!(f, "{}", numerator),
+            (false, true) => write!(f, "{}/{}", numerator, denom),
+            (false, false) => write!(f, "({})/{}", numerator, denom),
+        }
+    }
+}
+
+pub fn quadratic_roots(a: &BigRational, b: &BigRational, c: &BigRational) -> (Surd, Surd) {
+    let two_a = a + a;
+    let delta = b * b - BigRational::from_integer(BigInt::from(4)) * a * c;
+    // √(n/d) = √(n·d) / d
+    let coeff = BigRational::one() / (&two_a * BigRational::from_integer(delta.denom().clone()));
+    let radicand = delta.numer() * delta.denom();
+    (
+        Surd::new(-b / &two_a, -coeff.clone(), radicand.clone()),
+        Surd::new(-b / &two_a, coeff, radicand),
+    )
+}
+
+// Products of terms above which expanding a product takes too long
+const PRODUCT_BOUND: usize = 100_000;
+
+#[derive(Clone, PartialEq)]
+pub struct Polynomial<T = f64> {
+    terms: BTreeMap<u32, T>,
+}
+
+impl<T: Clone + Signed> Default for Polynomial<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone + Signed> Polynomial<T> {
+    pub fn new() -> Self {
+        Polynomial {
+            terms: BTreeMap::new(),
+        }
+    }
+
+    pub fn monomial(coeff: T, degree: u32) -> Self {
+        let mut polynomial = Polynomial::new();
+        polynomial.add_term(degree, coeff);
+        polynomial
+    }
+
+    pub fn constant(coeff: T) -> Self {
+        Polynomial::monomial(coeff, 0)
+    }
+
+    pub fn from_coeffs(coeffs: &[T]) -> Self {
+        let mut polynomial = Polynomial::new();
+        for (degree, coeff) in coeffs.iter().enumerate() {
+            polynomial.add_term(degree as u32, coeff.clone());
+        }
+        polynomial
+    }
+
+    fn add_term(&mut self, degree: u32, coeff: T) {
+        let val = self.terms.entry(degree).or_insert_with(T::zero);
+        *val = val.clone() + coeff;
+        if val.is_zero() {
+            self.terms.remove(&degree);
+        }
+    }
+
+    pub fn coeff(&self, degree: u32) -> T {
+        self.terms.get(&degree).cloned().unwrap_or_else(T::zero)
+    }
+
+    pub fn terms(&self) -> impl Iterator<Item = (u32, &T)> {
+        self.terms.iter().map(|(degree, coeff)| (*degree, coeff))
+    }
+
+    pub fn to_coeffs(&self) -> Vec<T> {
+        (0..=self.degree())
+            .map(|degree| self.coeff(degree))
+            .collect()
+    }
+
+    pub fn degree(&self) -> u32 {
+        self.terms.keys().next_back().copied().unwrap_or(0)
+    }
+
+    pub fn leading_coeff(&self) -> T {
+        self.coeff(self.degree())
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    pub fn normalise(&self) -> Self {
+        let leading = self.leading_coeff();
+        match leading.is_zero() {
+            true => self.clone(),
+            false => self.map(|coeff| coeff.clone() / leading.clone()),
+        }
+    }
+
+    pub fn map<U: Clone + Signed>(&self, f: impl Fn(&T) -> U) -> Polynomial<U> {
+        let mut polynomial = Polynomial::new();
+        for (degree, coeff) in self.terms() {
+            polynomial.add_term(degree, f(coeff));
+        }
+        polynomial
+    }
+
+    pub fn checked_mul(&self, other: &Polynomial<T>) -> Option<Self> {
+        if self.terms.len().saturating_mul(other.terms.len()) > PRODUCT_BOUND {
+            return None;
+        }
+        let mut product = Polynomial::new();
+        for (p_degree, p_coeff) in self.terms() {
+            for (q_degree, q_coeff) in other.terms() {
+                product.add_term(
+                    p_degree.checked_add(q_degree)?,
+                    p_coeff.clone() * q_coeff.clone(),
+                );
+            }
+        }
+        Some(product)
+    }
+
+    pub fn checked_pow(&self, exponent: u32) -> Option<Self> {
+        let mut result = Polynomial::constant(T::one());
+        let mut base = self.clone();
+        let mut exponent = exponent;
+        while exponent > 0 {
+            if exponent % 2 == 1 {
+                result = result.checked_mul(&base)?;
+            }
+            exponent /= 2;
+            if exponent > 0 {
+                base = base.checked_mul(&base)?;
+            }
+        }
+        Some(result)
+    }
+
+    pub fn pow(&self, exponent: u32) -> Self {
+        self.checked_pow(exponent)
+            .expect("the degree of the power overflows")
+    }
+
+    pub fn div_rem(&self, divisor: &Polynomial<T>) -> (Self, Self) {
+        assert!(!divisor.is_zero(), "division by the zero polynomial");
+        let mut quotient = Polynomial::new();
+        let mut remainder = self.clone();
+        let (degree, leading) = (divisor.degree(), divisor.leading_coeff());
+        while !remainder.is_zero() && remainder.degree() >= degree {
+            let term = Polynomial::monomial(
+                remainder.leading_coeff() / leading.clone(),
+                remainder.degree() - degree,
+            );
+            let previous = remainder.degree();
+            remainder = remainder - term.clone() * divisor.clone();
+            // rounding may leave the leading term alive, drop it
+            remainder.terms.remove(&previous);
+            quotient = quotient + term;
+        }
+        (quotient, remainder)
+    }
+
+    pub fn compose(&self, other: &Polynomial<T>) -> Self {
+        let mut result = Polynomial::new();
+        for degree in (0..=self.degree()).rev() {
+            result = result * other.clone() + Polynomial::constant(self.coeff(degree));
+        }
+        result
+    }
+
+    pub fn evaluate(&self, x: &T) -> T {
+        (0..=self.degree()).rev().fold(T::zero(), |acc, degree| {
+            acc * x.clone() + self.coeff(degree)
+        })
+    }
+}
+
+impl<T: Clone + Signed + FromPrimitive> Polynomial<T> {
+    pub fn derivative(&self) -> Self {
+        let mut derivative = Polynomial::new();
+        for (degree, coeff) in self.terms().filter(|(degree, _)| *degree > 0) {
+            let factor = T::from_u32(degree).expect("the degree is not representable");
+            derivative.add_term(degree - 1, coeff.clone() * factor);
+        }
+        derivative
+    }
+}
+
+// Euclid's algorithm needs exact remainders: with floats, rounding keeps them from reaching zero
+impl Polynomial<BigRational> {
+    // The same polynomial up to a constant, with coprime integer coefficients and a positive leading one
+    pub fn primitive(&self) -> Self {
+        let lcm = self
+            .terms()
+            .fold(BigInt::one(), |acc, (_, coeff)| acc.lcm(coeff.denom()));
+        let scaled = self.map(|coeff| coeff * BigRational::from_integer(lcm.clone()));
+        let mut content = scaled
+            .terms()
+            .fold(BigInt::zero(), |acc, (_, coeff)| acc.gcd(coeff.numer()));
+        if scaled.leading_coeff().is_negative() {
+            content = -content;
+        }
+        match content.is_zero() {
+            true => scaled,
+            false => scaled.map(|coeff| coeff / BigRational::from_integer(content.clone())),
+        }
+    }
+
+    // Primitive remainder sequence: the coefficients do not grow from one remainder to the next
+    pub fn gcd(&self, other: &Polynomial<BigRational>) -> Self {
+        let (mut a, mut b) = (self.primitive(), other.primitive());
+        while !b.is_zero() {
+            let (_, remainder) = a.div_rem(&b);
+            a = b;
+            b = remainder.primitive();
+        }
+        a.normalise()
+    }
+}
+
+impl Polynomial<f64> {
+    // A coefficient is noise when it is negligible next to the terms that summed into it
+    pub fn prune(&self, magnitude: &Polynomial, epsilon: f64) -> Self {
+        let mut pruned = Polynomial::new();
+        for (degree, coeff) in self.terms() {
+            if coeff.abs() > epsilon * magnitude.coeff(degree) {
+                pruned.add_term(degree, *coeff);
+            }
+        }
+        pruned
+    }
+
+    pub fn evaluate_complex(&self, z: &Complex) -> Complex {
+        (0..=self.degree())
+            .rev()
+            .fold(Complex::new(0.0, 0.0), |acc, degree| {
+                acc * *z + self.coeff(degree)
+            })
+    }
+}
+
+impl<T: Clone + Signed> Add for Polynomial<T> {
+    type Output = Polynomial<T>;
+
+    fn add(mut self, other: Polynomial<T>) -> Polynomial<T> {
+        for (degree, coeff) in other.terms {
+            self.add_term(degree, coeff);
+        }
+        self
+    }
+}
+
+impl<T: Clone + Signed> Sub for Polynomial<T> {
+    type Output = Polynomial<T>;
+
+    fn sub(mut self, other: Polynomial<T>) -> Polynomial<T> {
+        for (degree, coeff) in other.terms {
+            self.add_term(degree, -coeff);
+        }
+        self
+    }
+}
+
+impl<T: Clone + Signed> Mul for Polynomial<T> {
+    type Output = Polynomial<T>;
+
+    fn mul(self, other: Polynomial<T>) -> Polynomial<T> {
+        self.checked_mul(&other)
+            .expect("the degree of the product overflows")
+    }
+}
+
+impl<T: Clone + Signed> Neg for Polynomial<T> {
+    type Output = Polynomial<T>;
+
+    fn neg(self) -> Polynomial<T> {
+        self.map(|coeff| -coeff.clone())
+    }
+}
+
+pub struct NamedPolynomial<'a, T> {
+    polynomial: &'a Polynomial<T>,
+    variable: char,
+}
+
+impl<T: Clone + Signed> Polynomial<T> {
+    pub fn named(&self, variable: char) -> NamedPolynomial<'_, T> {
+        NamedPolynomial {
+            polynomial: self,
+            variable,
+        }
+    }
+}
+
+impl<T: Clone + Signed + fmt::Display> fmt::Display for NamedPolynomial<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let val = self.polynomial.coeff(0);
+        write!(
+            f,
+            "{}{} * {}^0",
+            if val.is_negative() { "- " } else { "" },
+            val.abs(),
+            self.variable
+        )?;
+        for deg in 1..=self.polynomial.degree() {
+            let val = self.polynomial.coeff(deg);
+            // the alternate form only lists the nonzero terms
+            if f.alternate() && val.is_zero() {
+                continue;
+            }
+            write!(
+                f,
+                " {} {} * {}^{}",
+                if val.is_negative() { "-" } else { "+" },
+                val.abs(),
+                self.variable,
+                deg
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: Clone + Signed + fmt::Display> fmt::Display for Polynomial<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.named('X'), f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+    use std::thread;
+    use std::time::Duration;
+
+    // Fails instead of hanging when the computation does not terminate
+    fn terminates<T: Send + 'static>(f: impl FnOnce() -> T + Send + 'static) -> T {
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || sender.send(f()));
+        receiver
+            .recv_timeout(Duration::from_secs(5))
+            .expect("the computation does not terminate")
+    }
+
+    fn rationals(coeffs: &[i64]) -> Polynomial<BigRational> {
+        Polynomial::from_coeffs(
+            &coeffs
+                .iter()
+                .map(|coeff| BigRational::from_integer(BigInt::from(*coeff)))
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    #[test]
+    fn polynomial_terms() {
+        let p = Polynomial::from_coeffs(&[1.0, 0.0, -2.0, 0.0]);
+        assert_eq!(p.degree(), 2);
+        assert_eq!(p.coeff(2), -2.0);
+        assert_eq!(p.coeff(7), 0.0);
+        assert_eq!(p.to_coeffs(), vec![1.0, 0.0, -2.0]);
+        assert_eq!(p.leading_coeff(), -2.0);
+        assert_eq!(p.evaluate(&3.0), -17.0);
+        assert!(p.normalise() == Polynomial::from_coeffs(&[-0.5, 0.0, 1.0]));
+        assert!(p.derivative() == Polynomial::monomial(-4.0, 1));
+        assert!((p.clone() - p).is_zero());
+        assert_eq!(Polynomial::<f64>::new().degree(), 0);
+    }
+
+    #[test]
+    fn polynomial_div_rem() {
+        // X^3 - 2X + 1 = (X - 1)(X^2 + X - 1)
+        let (quotient, remainder) = rationals(&[1, -2, 0, 1]).div_rem(&rationals(&[-1, 1]));
+        assert!(quotient == rationals(&[-1, 1, 1]));
+        assert!(remainder.is_zero());
+        let (quotient, remainder) = rationals(&[3, 0, 2]).div_rem(&rationals(&[1, 2]));
+        assert!(
+            quotient
+                == Polynomial::from_coeffs(&[
+                    BigRational::new(BigInt::from(-1), BigInt::from(2)),
+                    BigRational::one()
+                ])
+        );
+        assert!(
+            remainder
+                == Polynomial::constant(
+                    BigRational::from_integer(BigInt::from(7))
+                        / BigRational::from_integer(BigInt::from(2))
+                )
+        );
+        let (quotient, remainder) = Polynomial::from_coeffs(&[1.0, 2.0])
+            .div_rem(&Polynomial::from_coeffs(&[0.0, 0.0, 1.0]));
+        assert!(quotient.is_zero());
+        assert!(remainder == Polynomial::from_coeffs(&[1.0, 2.0]));
+    }
+
+    #[test]
+    fn polynomial_compose_and_pow() {
+        let p = Polynomial::from_coeffs(&[1.0, 0.0, 1.0]);
+        let q = Polynomial::from_coeffs(&[1.0, 1.0]);
+        assert!(p.compose(&q) == Polynomial::from_coeffs(&[2.0, 2.0, 1.0]));
+        assert!(q.compose(&p) == Polynomial::from_coeffs(&[2.0, 0.0, 1.0]));
+        assert!(q.pow(3) == Polynomial::from_coeffs(&[1.0, 3.0, 3.0, 1.0]));
+        assert!(q.pow(0) == Polynomial::constant(1.0));
+        assert!(q.checked_pow(1000).is_none());
+        assert!(q.checked_pow(100_000).is_none());
+        assert!(Polynomial::monomial(1.0, 1 << 30).checked_pow(4).is_none());
+        assert!(Polynomial::monomial(1.0, 1 << 30).checked_pow(3).is_some());
+    }
+
+    #[test]
+    fn polynomial_gcd() {
+        // (X + 1)(X + 2) and 3(X + 1)
+        let p = rationals(&[2, 3, 1]);
+        assert!(p.gcd(&rationals(&[3, 3])) == rationals(&[1, 1]));
+        assert!(p.gcd(&rationals(&[1, 0, 1])) == rationals(&[1]));
+        assert!(p.gcd(&p.derivative()) == rationals(&[1]));
+        let square = rationals(&[1, 1]).pow(2) * rationals(&[-1, 1]);
+        assert!(square.gcd(&square.derivative()) == rationals(&[1, 1]));
+    }
+
+    #[test]
+    fn polynomial_display() {
+        let p = Polynomial::from_coeffs(&[-1.0, 0.0, 2.5]);
+        assert_eq!(p.to_string(), "- 1 * X^0 + 0 * X^1 + 2.5 * X^2");
+        assert_eq!(format!("{:#}", p), "- 1 * X^0 + 2.5 * X^2");
+        assert_eq!(format!("{:#}", p.named('t')), "- 1 * t^0 + 2.5 * t^2");
+        assert_eq!(Polynomial::<f64>::new().to_string(), "0 * X^0");
+        assert_eq!(
+            rationals(&[1, -3])
+                .map(|coeff| coeff / BigRational::from_integer(BigInt::from(2)))
+                .to_string(),
+            "1/2 * X^0 - 3/2 * X^1"
+        );
+    }
+
+    #[test]
+    fn complex_division_does_not_overflow() {
+        let z = Complex::new(1e200, 1e200);
+        let quotient = z / z;
+        assert_eq!((quotient.real, quotient.imag), (1.0, 0.0));
+        let quotient = Complex::new(1e-200, 1e-200) / Complex::new(1e-200, -1e-200);
+        assert_eq!((quotient.real, quotient.imag), (0.0, 1.0));
+        let quotient = Complex::new(1e300, 0.0) / Complex::new(0.0, 1e-10);
+        assert!(quotient.real == 0.0 && quotient.imag.is_infinite());
+    }
+
+    #[test]
+    fn complex_division() {
+        let quotient = Complex::new(1.0, 2.0) / Complex::new(3.0, 4.0);
+        assert!(quotient.approx_eq(&Complex::new(0.44, 0.08), 1e-15));
+        let quotient = Complex::new(1.0, 0.0) / Complex::new(0.0, 2.0);
+        assert_eq!((quotient.real, quotient.imag), (0.0, -0.5));
+    }
+
+    #[test]
+    fn complex_scalar_operators() {
+        let z = Complex::new(1.0, 2.0);
+        let sum = 3.0 + z;
+        assert_eq!((sum.real, sum.imag), (4.0, 2.0));
+        let difference = 3.0 - z;
+        assert_eq!((difference.real, difference.imag), (2.0, -2.0));
+        let product = 3.0 * z;
+        assert_eq!((product.real, product.imag), (3.0, 6.0));
+        let quotient = 5.0 / z;
+        assert_eq!((quotient.real, quotient.imag), (1.0, -2.0));
+        let quotient = 1.0 / Complex::new(1e200, 1e200);
+        assert!(quotient.approx_eq(&Complex::new(5e-201, -5e-201), 1e-15));
+        assert!(quotient.real != 0.0);
+    }
+
+    #[test]
+    fn complex_powi() {
+        let z = Complex::new(1.0, 1.0);
+        let power = z.powi(0);
+        assert_eq!((power.real, power.imag), (1.0, 0.0));
+        let power = z.powi(2);
+        assert_eq!((power.real, power.imag), (0.0, 2.0));
+        let power = z.powi(8);
+        assert_eq!((power.real, power.imag), (16.0, 0.0));
+        let power = z.powi(-2);
+        assert_eq!((power.real, power.imag), (0.0, -0.5));
+        let power = Complex::new(0.0, 1.0).powi(7);
+        assert_eq!((power.real, power.imag), (0.0, -1.0));
+        assert!(Complex::new(-2.0, 0.5)
+            .powi(5)
+            .approx_eq(&Complex::new(-12.625, 35.03125), 1e-15));
+    }
+
+    #[test]
+    fn complex_exp() {
+        let value = Complex::new(0.0, 0.0).exp();
+        assert_eq!((value.real, value.imag), (1.0, 0.0));
+        let value = Complex::new(1.0, 0.0).exp();
+        assert_eq!((value.real, value.imag), (std::f64::consts::E, 0.0));
+        // Euler's identity
+        let value = Complex::new(0.0, std::f64::consts::PI).exp();
+        assert!(value.approx_eq(&Complex::new(-1.0, 0.0), 1e-15));
+        let value = Complex::new(2.0_f64.ln(), std::f64::consts::FRAC_PI_2).exp();
+        assert!(value.approx_eq(&Complex::new(0.0, 2.0), 1e-15));
+    }
+
+    #[test]
+    fn complex_polar() {
+        let z = Complex::from_polar(2.0, std::f64::consts::FRAC_PI_2);
+        assert!(z.approx_eq(&Complex::new(0.0, 2.0), 1e-15));
+        let (modulus, arg) = Complex::new(-3.0, 0.0).to_polar();
+        assert_eq!((modulus, arg), (3.0, std::f64::consts::PI));
+        let (modulus, arg) = Complex::new(3.0, -4.0).to_polar();
+        assert_eq!(modulus, 5.0);
+        assert!((arg + (4.0_f64 / 3.0).atan()).abs() <= 1e-15);
+        let z = Complex::new(-1.5, 0.25);
+        let (modulus, arg) = z.to_polar();
+        assert!(Complex::from_polar(modulus, arg).approx_eq(&z, 1e-15));
+        assert_eq!(Complex::from_polar(0.0, 1.0).abs(), 0.0);
+    }
+
+    #[test]
+    fn complex_approx_eq() {
+        let z = Complex::new(1.0, -1.0);
+        assert!(z.approx_eq(&Complex::new(1.0 + 1e-13, -1.0), 1e-12));
+        assert!(!z.approx_eq(&Complex::new(1.0 + 1e-11, -1.0), 1e-12));
+        // relative to the largest modulus above 1, absolute below
+        assert!(Complex::new(1e20, 0.0).approx_eq(&Complex::new(1e20 + 1e5, 0.0), 1e-12));
+        assert!(!Complex::new(1e-20, 0.0).approx_eq(&Complex::new(0.0, 1e-11), 1e-12));
+        assert!(Complex::new(1e-20, 0.0).approx_eq(&Complex::new(0.0, 1e-13), 1e-12));
+    }
+
+    #[test]
+    fn complex_display() {
+        assert_eq!(Complex::new(1.5, 0.0).to_string(), "1.50");
+        assert_eq!(Complex::new(-0.001, 0.0).to_string(), "0.00");
+        assert_eq!(Complex::new(1.0, -2.5).to_string(), "1.00 - 2.50 * i");
+        assert_eq!(Complex::new(0.0, 0.25).to_string(), "0.00 + 0.25 * i");
+        assert_eq!(Complex::new(1.0, 0.0001).to_string(), "1.00");
+        assert_eq!(
+            format!("{:.4}", Complex::new(-1.0, 0.0001)),
+            "-1.0000 + 0.0001 * i"
+        );
+    }
+
+    // xorshift64, enough to spread the probes over every exponent
+    fn probes(count: usize) -> impl Iterator<Item = f64> {
+        let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+        (0..count).map(move |_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            f64::from_bits(state)
+        })
+    }
+
+    fn assert_sqrt(number: f64) {
+        match sqrt(number) {
+            Some(root) if number.is_nan() => assert!(root.is_nan()),
+            Some(root) => assert_eq!(
+                root.to_bits(),
+                number.sqrt().to_bits(),
+                "sqrt({:e})",
+                number
+            ),
+            None => assert!(number < 0.0, "sqrt({:e}) is missing", number),
+        }
+    }
+
+    #[test]
+    fn sqrt_of_special_numbers() {
+        assert_eq!(sqrt(0.0).map(f64::to_bits), Some(0.0_f64.to_bits()));
+        assert_eq!(sqrt(-0.0).map(f64::to_bits), Some((-0.0_f64).to_bits()));
+        assert_eq!(sqrt(f64::INFINITY), Some(f64::INFINITY));
+        assert!(sqrt(f64::NAN).unwrap().is_nan());
+        assert_eq!(sqrt(f64::NEG_INFINITY), None);
+        assert_eq!(sqrt(-1.0), None);
+        assert_eq!(sqrt(-f64::MIN_POSITIVE / 2.0), None);
+        for number in [
+            1.0,
+            2.0,
+            4.0,
+            f64::MAX,
+            f64::MIN_POSITIVE,
+            f64::MIN_POSITIVE / 2.0,
+            f64::from_bits(1),
+            f64::from_bits(0x000f_ffff_ffff_ffff),
+            4.0 - f64::EPSILON * 2.0,
+            1.0 + f64::EPSILON,
+        ] {
+            assert_sqrt(number);
+        }
+    }
+
+    #[test]
+    fn sqrt_is_correctly_rounded() {
+        for number in probes(200_000) {
+            assert_sqrt(number);
+        }
+        // and on subnormals
+        for number in probes(20_000) {
+            assert_sqrt(f64::from_bits(number.to_bits() & 0x000f_ffff_ffff_ffff));
+        }
+    }
+
+    #[test]
+    fn cbrt_of_non_finite_numbers() {
+        assert!(terminates(|| cbrt(f64::NAN)).is_nan());
+        assert_eq!(terminates(|| cbrt(f64::INFINITY)), f64::INFINITY);
+        assert_eq!(terminates(|| cbrt(f64::NEG_INFINITY)), f64::NEG_INFINITY);
+        assert_eq!(cbrt(0.0), 0.0);
+    }
+
+    #[test]
+    fn cbrt_of_finite_numbers() {
+        assert_eq!(cbrt(27.0), 3.0);
+        assert_eq!(cbrt(-8.0), -2.0);
+        for number in [1e-300, 0.001, 2.0, 1e300, f64::MAX, f64::MIN_POSITIVE / 4.0] {
+            let root = terminates(move || cbrt(number));
+            assert!((root - number.cbrt()).abs() <= 1e-8 * number.cbrt());
+        }
+    }
+
+    #[test]
+    fn chop_keeps_non_finite_values() {
+        assert_eq!(chop(1e-20, &[1.0, 1.0], 1e-12), 0.0);
+        assert_eq!(chop(1e-6, &[1.0, 1.0], 1e-12), 1e-6);
+        assert_eq!(
+            chop(f64::INFINITY, &[f64::INFINITY, 1.0], 1e-12),
+            f64::INFINITY
+        );
+        assert!(chop(f64::NAN, &[f64::INFINITY, f64::INFINITY], 1e-12).is_nan());
+    }
+
+    #[test]
+    fn polynomial_prune() {
+        let polynomial = Polynomial::from_coeffs(&[5.5e-17, 1.0, 1e-17]);
+        let magnitude = Polynomial::from_coeffs(&[0.6, 1.0, 1e-17]);
+        assert!(polynomial.prune(&magnitude, 1e-12) == Polynomial::from_coeffs(&[0.0, 1.0, 1e-17]));
+        assert!(polynomial.prune(&magnitude, 0.0) == polynomial);
+    }
+
+    #[test]
+    fn scaled_ratio_does_not_overflow() {
+        assert_eq!(scaled_ratio(3.0, 2.0, 1), 3.0);
+        assert_eq!(scaled_ratio(-3.0, 4.0, 0), -0.75);
+        assert_eq!(
+            scaled_ratio(f64::MAX, f64::MIN_POSITIVE, -2046),
+            1.0 - f64::EPSILON / 2.0
+        );
+        assert!((scaled_ratio(f64::MIN_POSITIVE, -f64::MAX, 2046) + 1.0).abs() <= f64::EPSILON);
+        assert_eq!(scaled_ratio(0.0, 1e-300, 2000), 0.0);
+    }
+
+    #[test]
+    fn scaled_display() {
+        assert_eq!(Scaled::new(4.0, 0).to_string(), "4");
+        assert_eq!(Scaled::new(1.5, 2).to_string(), "6");
+        assert_eq!(Scaled::new(-1.0, 2000).to_string(), "-1.1481306953e602");
+        assert_eq!(Scaled::new(1.0, -2000).to_string(), "8.7098098162e-603");
+        assert_eq!(Scaled::new(0.0, 2000).to_string(), "0");
     }
-    ans
 }
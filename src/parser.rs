@@ -1,31 +1,45 @@
-use std::collections::HashMap;
 use std::error::Error;
 use std::fmt;
 
+use crate::ast::{Equation, Expr};
 use crate::lexer::Lexem;
+use crate::maths::Polynomial;
+use num_traits::Signed;
 
 pub struct Parser {
-    degrees: HashMap<u32, f64>,
+    equation: Option<Equation>,
+    polynomial: Polynomial,
+    magnitude: Polynomial,
+    denominators: Vec<Denominator>,
+    variable: char,
+    strict: bool,
+    errors: Vec<ParseError>,
+    depth: usize,
 }
 
-impl<'a> Parser {
-    pub fn new() -> Self {
-        Parser {
-            degrees: HashMap::new(),
-        }
+impl Default for Parser {
+    fn default() -> Self {
+        Self::new()
     }
+}
 
-    fn update_hashmap(&mut self, term: &Term) {
-        let val: &mut f64 = self.degrees.entry(term.degree).or_insert(0.0);
-        *val += term.coeff;
-        if *val == 0.0 && term.degree != 0 {
-            self.degrees.remove(&term.degree);
+impl Parser {
+    pub fn new() -> Self {
+        Parser {
+            equation: None,
+            polynomial: Polynomial::new(),
+            magnitude: Polynomial::new(),
+            denominators: Vec::new(),
+            variable: 'X',
+            strict: false,
+            errors: Vec::new(),
+            depth: 0,
         }
     }
 
-    fn get_next_lexem(
+    fn get_next_lexem<'a>(
         &self,
-        lexems: &'a Vec<Lexem>,
+        lexems: &'a [Lexem],
         cursor: &mut usize,
     ) -> Result<&'a Lexem, ParseError> {
         if *cursor == lexems.len() {
@@ -38,7 +52,7 @@ impl<'a> Parser {
 
     fn check_expected_terminal_symbol(
         &self,
-        lexems: &Vec<Lexem>,
+        lexems: &[Lexem],
         cursor: &mut usize,
         target: &Lexem,
     ) -> Result<(), ParseError> {
@@ -49,7 +63,53 @@ impl<'a> Parser {
         }
     }
 
-    fn degree(&mut self, lexems: &Vec<Lexem>, cursor: &mut usize) -> Result<u32, ParseError> {
+    fn is_synchronizing(&self, lexem: &Lexem) -> bool {
+        match lexem {
+            Lexem::Plus { .. } | Lexem::Minus { .. } | Lexem::Equal { .. } | Lexem::End { .. } => {
+                true
+            }
+            Lexem::CloseParenthesis { .. } => self.depth > 0,
+            _ => false,
+        }
+    }
+
+    fn record_error(&mut self, err: ParseError) {
+        if let Some(last) = self.errors.last() {
+            // Once an error is recorded, reaching END early is only its consequence
+            if last.get_span() == err.get_span()
+                || matches!(err, ParseError::UnexpectedToken(Lexem::End { .. }))
+            {
+                return;
+            }
+        }
+        self.errors.push(err);
+    }
+
+    fn recover(&mut self, lexems: &[Lexem], cursor: &mut usize, err: ParseError) -> Expr {
+        if let Some(index) = err.get_index() {
+            if let Some(position) = lexems.iter().position(|lexem| lexem.get_index() == index) {
+                *cursor = position;
+            }
+        }
+        self.record_error(err);
+        while *cursor < lexems.len() - 1 && !self.is_synchronizing(&lexems[*cursor]) {
+            *cursor += 1;
+        }
+        Expr::Number {
+            value: 0.0,
+            index: lexems[*cursor].get_index(),
+            len: 0,
+        }
+    }
+
+    fn recovered_term(&mut self, lexems: &[Lexem], cursor: &mut usize) -> Expr {
+        match self.term(lexems, cursor) {
+            Ok(term) => term,
+            Err(err) => self.recover(lexems, cursor, err),
+        }
+    }
+
+    fn degree(&mut self, lexems: &[Lexem], cursor: &mut usize) -> Result<Option<u32>, ParseError> {
         match self.get_next_lexem(lexems, cursor)? {
             lexem if *lexem == Lexem::Power { index: 0, len: 0 } => {
                 match self.get_next_lexem(lexems, cursor)? {
@@ -67,37 +127,50 @@ impl<'a> Parser {
                         {
                             return Err(ParseError::NotUIntegerDegree(*lexem_2));
                         }
-                        Ok(degree as u32)
+                        Ok(Some(degree as u32))
                     }
                     lexem_2 => Err(ParseError::UnexpectedToken(*lexem_2)),
                 }
             }
             _ => {
                 *cursor -= 1;
-                Ok(1)
+                Ok(None)
             }
         }
     }
 
-    fn term_end(&mut self, lexems: &Vec<Lexem>, cursor: &mut usize) -> Result<u32, ParseError> {
-        match self.get_next_lexem(lexems, cursor)? {
-            lexem if *lexem == Lexem::Mult { index: 0, len: 0 } => {
-                self.check_expected_terminal_symbol(
-                    lexems,
-                    cursor,
-                    &Lexem::X { index: 0, len: 0 },
-                )?;
-                self.degree(lexems, cursor)
-            }
-            lexem if *lexem == Lexem::X { index: 0, len: 0 } => self.degree(lexems, cursor),
-            _ => {
-                *cursor -= 1;
-                Ok(0)
+    fn powered(
+        &mut self,
+        lexems: &[Lexem],
+        cursor: &mut usize,
+        base: Expr,
+    ) -> Result<Expr, ParseError> {
+        match self.degree(lexems, cursor)? {
+            Some(exponent) => {
+                let last = &lexems[*cursor - 1];
+                Ok(Expr::Power {
+                    index: base.get_index(),
+                    len: last.get_index() + last.get_len() - base.get_index(),
+                    base: Box::new(base),
+                    exponent,
+                })
             }
+            None => Ok(base),
         }
     }
 
-    fn term(&mut self, lexems: &Vec<Lexem>, cursor: &mut usize) -> Result<Term, ParseError> {
+    fn identifier(&mut self, lexem: &Lexem) -> Expr {
+        if lexem.get_name() != self.variable {
+            self.record_error(ParseError::OtherVariable(*lexem, self.variable));
+        }
+        Expr::Identifier {
+            name: lexem.get_name(),
+            index: lexem.get_index(),
+            len: lexem.get_len(),
+        }
+    }
+
+    fn factor(&mut self, lexems: &[Lexem], cursor: &mut usize) -> Result<Expr, ParseError> {
         match self.get_next_lexem(lexems, cursor)? {
             lexem
                 if *lexem
@@ -107,104 +180,476 @@ impl<'a> Parser {
                         len: 0,
                     } =>
             {
-                Ok(Term {
-                    coeff: lexem.get_value(),
-                    degree: self.term_end(lexems, cursor)?,
+                Ok(Expr::Number {
+                    value: lexem.get_value(),
+                    index: lexem.get_index(),
+                    len: lexem.get_len(),
                 })
             }
-            lexem if *lexem == Lexem::X { index: 0, len: 0 } => Ok(Term {
-                coeff: 1.0,
-                degree: self.degree(lexems, cursor)?,
-            }),
+            lexem
+                if *lexem
+                    == Lexem::Identifier {
+                        name: 'X',
+                        index: 0,
+                        len: 0,
+                    } =>
+            {
+                let x = self.identifier(lexem);
+                self.powered(lexems, cursor, x)
+            }
+            lexem if *lexem == Lexem::OpenParenthesis { index: 0, len: 0 } => {
+                self.depth += 1;
+                let inner = self.expression(lexems, cursor);
+                self.depth -= 1;
+                let inner = inner?;
+                self.check_expected_terminal_symbol(
+                    lexems,
+                    cursor,
+                    &Lexem::CloseParenthesis { index: 0, len: 0 },
+                )?;
+                let last = &lexems[*cursor - 1];
+                let group = Expr::Group {
+                    inner: Box::new(inner),
+                    index: lexem.get_index(),
+                    len: last.get_index() + last.get_len() - lexem.get_index(),
+                };
+                self.powered(lexems, cursor, group)
+            }
             lexem => Err(ParseError::UnexpectedToken(*lexem)),
         }
     }
 
-    fn expression_end(
+    // A number after a factor is not a product: `X2` is more likely a mistyped `X^2`
+    fn is_implicit_factor(&self, lexem: &Lexem) -> bool {
+        matches!(
+            lexem,
+            Lexem::Identifier { .. } | Lexem::OpenParenthesis { .. }
+        )
+    }
+
+    fn term_end(
         &mut self,
-        lexems: &Vec<Lexem>,
+        lexems: &[Lexem],
         cursor: &mut usize,
-        member: Member,
-    ) -> Result<(), ParseError> {
-        let mut sign: i32 = match member {
-            Member::Left => 1,
-            Member::Right => -1,
-        };
+        product: Expr,
+    ) -> Result<Expr, ParseError> {
         match self.get_next_lexem(lexems, cursor)? {
-            lexem if *lexem == Lexem::Plus { index: 0, len: 0 } => (),
-            lexem if *lexem == Lexem::Minus { index: 0, len: 0 } => sign *= -1,
+            lexem if *lexem == Lexem::Mult { index: 0, len: 0 } => {
+                let factor = self.factor(lexems, cursor)?;
+                let product = Expr::Mult {
+                    index: product.get_index(),
+                    len: factor.get_end() - product.get_index(),
+                    left: Box::new(product),
+                    right: Box::new(factor),
+                };
+                self.term_end(lexems, cursor, product)
+            }
+            lexem if *lexem == Lexem::Div { index: 0, len: 0 } => {
+                let divisor = self.factor(lexems, cursor)?;
+                let quotient = Expr::Div {
+                    index: product.get_index(),
+                    len: divisor.get_end() - product.get_index(),
+                    left: Box::new(product),
+                    right: Box::new(divisor),
+                };
+                self.term_end(lexems, cursor, quotient)
+            }
+            lexem if self.is_implicit_factor(lexem) => {
+                if self.strict {
+                    self.record_error(ParseError::ImplicitMultiplication(*lexem));
+                }
+                *cursor -= 1;
+                let factor = self.factor(lexems, cursor)?;
+                let product = Expr::Mult {
+                    index: product.get_index(),
+                    len: factor.get_end() - product.get_index(),
+                    left: Box::new(product),
+                    right: Box::new(factor),
+                };
+                self.term_end(lexems, cursor, product)
+            }
             _ => {
                 *cursor -= 1;
-                return Ok(());
+                Ok(product)
             }
-        };
-        let mut term = self.term(lexems, cursor)?;
-        term.coeff *= sign as f64;
-        self.update_hashmap(&term);
-        self.expression_end(lexems, cursor, member)
+        }
     }
 
-    fn expression(
+    fn term(&mut self, lexems: &[Lexem], cursor: &mut usize) -> Result<Expr, ParseError> {
+        let factor = self.factor(lexems, cursor)?;
+        self.term_end(lexems, cursor, factor)
+    }
+
+    fn expression_end(
         &mut self,
-        lexems: &Vec<Lexem>,
+        lexems: &[Lexem],
         cursor: &mut usize,
-        member: Member,
-    ) -> Result<(), ParseError> {
-        let mut sign: i32 = match member {
-            Member::Left => 1,
-            Member::Right => -1,
+        sum: Expr,
+    ) -> Result<Expr, ParseError> {
+        let lexem = self.get_next_lexem(lexems, cursor)?;
+        if *lexem != (Lexem::Plus { index: 0, len: 0 })
+            && *lexem != (Lexem::Minus { index: 0, len: 0 })
+        {
+            *cursor -= 1;
+            return Ok(sum);
+        }
+        let term = self.recovered_term(lexems, cursor);
+        let (index, len) = (sum.get_index(), term.get_end() - sum.get_index());
+        let (left, right) = (Box::new(sum), Box::new(term));
+        let sum = match *lexem == (Lexem::Plus { index: 0, len: 0 }) {
+            true => Expr::Add {
+                left,
+                right,
+                index,
+                len,
+            },
+            false => Expr::Sub {
+                left,
+                right,
+                index,
+                len,
+            },
         };
-        match self.get_next_lexem(lexems, cursor)? {
-            lexem if *lexem == Lexem::Plus { index: 0, len: 0 } => (),
-            lexem if *lexem == Lexem::Minus { index: 0, len: 0 } => sign *= -1,
-            _ => *cursor -= 1,
+        self.expression_end(lexems, cursor, sum)
+    }
+
+    fn expression(&mut self, lexems: &[Lexem], cursor: &mut usize) -> Result<Expr, ParseError> {
+        let first = match self.get_next_lexem(lexems, cursor)? {
+            lexem if *lexem == Lexem::Plus { index: 0, len: 0 } => {
+                self.recovered_term(lexems, cursor)
+            }
+            lexem if *lexem == Lexem::Minus { index: 0, len: 0 } => {
+                let operand = self.recovered_term(lexems, cursor);
+                Expr::Neg {
+                    index: lexem.get_index(),
+                    len: operand.get_end() - lexem.get_index(),
+                    operand: Box::new(operand),
+                }
+            }
+            _ => {
+                *cursor -= 1;
+                self.recovered_term(lexems, cursor)
+            }
         };
-        let mut term = self.term(lexems, cursor)?;
-        term.coeff *= sign as f64;
-        self.update_hashmap(&term);
-        self.expression_end(lexems, cursor, member)
+        self.expression_end(lexems, cursor, first)
     }
 
-    fn equation(&mut self, lexems: &Vec<Lexem>) -> Result<(), ParseError> {
+    fn equation(&mut self, lexems: &[Lexem]) -> Result<Equation, ParseError> {
         let mut cursor: usize = 0;
-        self.expression(lexems, &mut cursor, Member::Left)?;
-        self.check_expected_terminal_symbol(
+        let lhs = self.expression(lexems, &mut cursor)?;
+        if let Err(err) = self.check_expected_terminal_symbol(
             lexems,
             &mut cursor,
             &Lexem::Equal { index: 0, len: 0 },
-        )?;
-        self.expression(lexems, &mut cursor, Member::Right)?;
-        self.check_expected_terminal_symbol(lexems, &mut cursor, &Lexem::End { index: 0, len: 0 })
+        ) {
+            self.record_error(err);
+            cursor -= 1;
+            while cursor < lexems.len() - 1 && lexems[cursor] != (Lexem::Equal { index: 0, len: 0 })
+            {
+                cursor += 1;
+            }
+            if lexems[cursor] == (Lexem::Equal { index: 0, len: 0 }) {
+                cursor += 1;
+            }
+        }
+        let rhs = self.expression(lexems, &mut cursor)?;
+        if let Err(err) = self.check_expected_terminal_symbol(
+            lexems,
+            &mut cursor,
+            &Lexem::End { index: 0, len: 0 },
+        ) {
+            self.record_error(err);
+        }
+        Ok(Equation { lhs, rhs })
+    }
+
+    pub fn run(
+        &mut self,
+        lexems: &[Lexem],
+        variable: Option<char>,
+        strict: bool,
+    ) -> Result<(), Vec<ParseError>> {
+        self.strict = strict;
+        self.variable = variable
+            .or_else(|| {
+                lexems.iter().find_map(|lexem| match lexem {
+                    Lexem::Identifier { name, .. } => Some(*name),
+                    _ => None,
+                })
+            })
+            .unwrap_or('X');
+        self.polynomial = Polynomial::new();
+        self.magnitude = Polynomial::new();
+        self.denominators = Vec::new();
+        self.equation = None;
+        self.errors = Vec::new();
+        self.depth = 0;
+        if lexems.is_empty() {
+            return Err(vec![ParseError::NoTokenProvided()]);
+        }
+        match self.equation(lexems) {
+            Ok(equation) => self.equation = Some(equation),
+            Err(err) => self.record_error(err),
+        }
+        if !self.errors.is_empty() {
+            return Err(std::mem::take(&mut self.errors));
+        }
+        let equation = self.equation.as_ref().unwrap();
+        let reduction = reduce(equation, &|value, _, _| Ok(value)).map_err(|err| vec![err])?;
+        self.polynomial = reduction.polynomial;
+        self.magnitude = reduction.magnitude;
+        self.denominators = reduction.denominators;
+        Ok(())
+    }
+
+    pub fn get_equation(&self) -> Option<&Equation> {
+        self.equation.as_ref()
+    }
+
+    pub fn get_polynomial(&self) -> &Polynomial {
+        &self.polynomial
+    }
+
+    pub fn get_magnitude(&self) -> &Polynomial {
+        &self.magnitude
     }
 
-    pub fn run(&mut self, lexems: &Vec<Lexem>) -> Result<(), ParseError> {
-        self.degrees = HashMap::new();
-        self.degrees.insert(0, 0.0);
-        self.equation(lexems)
+    pub fn get_denominators(&self) -> &Vec<Denominator> {
+        &self.denominators
     }
 
-    pub fn get_degrees(&self) -> &HashMap<u32, f64> {
-        &self.degrees
+    pub fn get_variable(&self) -> char {
+        self.variable
     }
 }
 
-#[derive(Copy, Clone)]
-enum Member {
-    Left,
-    Right,
+pub struct Denominator<T = f64> {
+    pub text: String,
+    pub polynomial: Polynomial<T>,
+    pub magnitude: Polynomial<T>,
+}
+
+// The sum of the absolute values of the terms behind each coefficient, to tell cancellation noise
+pub struct Reduction<T = f64> {
+    pub polynomial: Polynomial<T>,
+    pub magnitude: Polynomial<T>,
+    pub denominators: Vec<Denominator<T>>,
+}
+
+struct Fraction<T> {
+    numerator: Polynomial<T>,
+    denominator: Polynomial<T>,
+    magnitude: Polynomial<T>,
+}
+
+fn absolute<T: Clone + Signed>(polynomial: &Polynomial<T>) -> Polynomial<T> {
+    polynomial.map(|coeff| coeff.abs())
+}
+
+fn fraction<T: Clone + Signed>(
+    numerator: Polynomial<T>,
+    denominator: Polynomial<T>,
+    magnitude: Polynomial<T>,
+) -> Fraction<T> {
+    match denominator.degree() {
+        0 => Fraction {
+            numerator: numerator.map(|coeff| coeff.clone() / denominator.coeff(0)),
+            magnitude: magnitude.map(|coeff| coeff.clone() / denominator.coeff(0).abs()),
+            denominator: Polynomial::constant(T::one()),
+        },
+        _ => Fraction {
+            numerator,
+            denominator,
+            magnitude,
+        },
+    }
 }
 
-struct Term {
-    coeff: f64,
-    degree: u32,
+// Brings both fractions to the same denominator, so that their numerators can be summed
+fn common_denominator<T: Clone + Signed>(
+    left: Fraction<T>,
+    right: Fraction<T>,
+    too_big: impl Fn() -> ParseError,
+) -> Result<(Fraction<T>, Fraction<T>), ParseError> {
+    if left.denominator == right.denominator {
+        return Ok((left, right));
+    }
+    let multiply = |fraction: &Fraction<T>, other: &Fraction<T>| {
+        Some(Fraction {
+            numerator: fraction.numerator.checked_mul(&other.denominator)?,
+            denominator: fraction.denominator.checked_mul(&other.denominator)?,
+            magnitude: fraction
+                .magnitude
+                .checked_mul(&absolute(&other.denominator))?,
+        })
+    };
+    match (multiply(&left, &right), multiply(&right, &left)) {
+        (Some(left), Some(right)) => Ok((left, right)),
+        _ => Err(too_big()),
+    }
+}
+
+fn reduce_expr<T, F>(
+    expr: &Expr,
+    number: &F,
+    denominators: &mut Vec<Denominator<T>>,
+) -> Result<Fraction<T>, ParseError>
+where
+    T: Clone + Signed,
+    F: Fn(f64, usize, usize) -> Result<T, ParseError>,
+{
+    let too_big = || ParseError::TooBigDegree(expr.get_index(), expr.get_len());
+    let one = || Polynomial::constant(T::one());
+    match expr {
+        Expr::Number { value, index, len } => {
+            let value = number(*value, *index, *len)?;
+            Ok(Fraction {
+                magnitude: Polynomial::constant(value.abs()),
+                numerator: Polynomial::constant(value),
+                denominator: one(),
+            })
+        }
+        Expr::Identifier { .. } => Ok(Fraction {
+            numerator: Polynomial::monomial(T::one(), 1),
+            denominator: one(),
+            magnitude: Polynomial::monomial(T::one(), 1),
+        }),
+        Expr::Group { inner, .. } => reduce_expr(inner, number, denominators),
+        Expr::Neg { operand, .. } => {
+            let operand = reduce_expr(operand, number, denominators)?;
+            Ok(Fraction {
+                numerator: -operand.numerator,
+                ..operand
+            })
+        }
+        Expr::Add { left, right, .. } | Expr::Sub { left, right, .. } => {
+            let left = reduce_expr(left, number, denominators)?;
+            let right = reduce_expr(right, number, denominators)?;
+            let (left, right) = common_denominator(left, right, too_big)?;
+            let right_numerator = match expr {
+                Expr::Sub { .. } => -right.numerator,
+                _ => right.numerator,
+            };
+            Ok(fraction(
+                left.numerator + right_numerator,
+                left.denominator,
+                left.magnitude + right.magnitude,
+            ))
+        }
+        Expr::Mult { left, right, .. } => {
+            let left = reduce_expr(left, number, denominators)?;
+            let right = reduce_expr(right, number, denominators)?;
+            Ok(fraction(
+                left.numerator
+                    .checked_mul(&right.numerator)
+                    .ok_or_else(too_big)?,
+                left.denominator
+                    .checked_mul(&right.denominator)
+                    .ok_or_else(too_big)?,
+                left.magnitude
+                    .checked_mul(&right.magnitude)
+                    .ok_or_else(too_big)?,
+            ))
+        }
+        Expr::Div { left, right, .. } => {
+            let dividend = reduce_expr(left, number, denominators)?;
+            let divisor = reduce_expr(right, number, denominators)?;
+            if divisor.numerator.is_zero() {
+                return Err(ParseError::DivisionByZero(
+                    right.get_index(),
+                    right.get_len(),
+                ));
+            }
+            if divisor.numerator.degree() > 0 {
+                denominators.push(Denominator {
+                    text: right.to_string(),
+                    polynomial: divisor.numerator.clone(),
+                    magnitude: divisor.magnitude.clone(),
+                });
+            }
+            Ok(fraction(
+                dividend
+                    .numerator
+                    .checked_mul(&divisor.denominator)
+                    .ok_or_else(too_big)?,
+                dividend
+                    .denominator
+                    .checked_mul(&divisor.numerator)
+                    .ok_or_else(too_big)?,
+                dividend
+                    .magnitude
+                    .checked_mul(&absolute(&divisor.denominator))
+                    .ok_or_else(too_big)?,
+            ))
+        }
+        Expr::Power { base, exponent, .. } => {
+            let base = reduce_expr(base, number, denominators)?;
+            Ok(Fraction {
+                numerator: base.numerator.checked_pow(*exponent).ok_or_else(too_big)?,
+                denominator: base
+                    .denominator
+                    .checked_pow(*exponent)
+                    .ok_or_else(too_big)?,
+                magnitude: base.magnitude.checked_pow(*exponent).ok_or_else(too_big)?,
+            })
+        }
+    }
+}
+
+// Both sides are multiplied by the denominators, so that the equation becomes polynomial
+pub fn reduce<T, F>(equation: &Equation, number: &F) -> Result<Reduction<T>, ParseError>
+where
+    T: Clone + Signed,
+    F: Fn(f64, usize, usize) -> Result<T, ParseError>,
+{
+    let mut denominators = Vec::new();
+    let left = reduce_expr(&equation.lhs, number, &mut denominators)?;
+    let right = reduce_expr(&equation.rhs, number, &mut denominators)?;
+    let too_big = || ParseError::TooBigDegree(equation.lhs.get_index(), equation.lhs.get_len());
+    let (left, right) = common_denominator(left, right, too_big)?;
+    Ok(Reduction {
+        polynomial: left.numerator - right.numerator,
+        magnitude: left.magnitude + right.magnitude,
+        denominators,
+    })
 }
 
 pub enum ParseError {
     UnexpectedToken(Lexem),
     NotUIntegerDegree(Lexem),
+    TooBigDegree(usize, usize),
+    DivisionByZero(usize, usize),
+    OtherVariable(Lexem, char),
+    ImplicitMultiplication(Lexem),
     NoTokenProvided(),
 }
 
+impl ParseError {
+    pub fn get_index(&self) -> Option<usize> {
+        match self {
+            ParseError::UnexpectedToken(lexem) => Some(lexem.get_index()),
+            ParseError::NotUIntegerDegree(lexem) => Some(lexem.get_index()),
+            ParseError::TooBigDegree(index, _) => Some(*index),
+            ParseError::DivisionByZero(index, _) => Some(*index),
+            ParseError::OtherVariable(lexem, _) => Some(lexem.get_index()),
+            ParseError::ImplicitMultiplication(lexem) => Some(lexem.get_index()),
+            ParseError::NoTokenProvided() => None,
+        }
+    }
+
+    pub fn get_span(&self) -> Option<(usize, usize)> {
+        match self {
+            ParseError::UnexpectedToken(lexem) => Some((lexem.get_index(), lexem.get_len())),
+            ParseError::NotUIntegerDegree(lexem) => Some((lexem.get_index(), lexem.get_len())),
+            ParseError::TooBigDegree(index, len) => Some((*index, *len)),
+            ParseError::DivisionByZero(index, len) => Some((*index, *len)),
+            ParseError::OtherVariable(lexem, _) => Some((lexem.get_index(), lexem.get_len())),
+            ParseError::ImplicitMultiplication(lexem) => Some((lexem.get_index(), lexem.get_len())),
+            ParseError::NoTokenProvided() => None,
+        }
+    }
+}
+
 impl fmt::Display for ParseError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
@@ -220,15 +665,213 @@ impl fmt::Display for ParseError {
                 lexem.get_value(),
                 lexem.get_index()
             ),
+            ParseError::TooBigDegree(index, _) => write!(
+                f,
+                "The degree of the term starting at index {} is too big",
+                index
+            ),
+            ParseError::DivisionByZero(index, _) => {
+                write!(f, "The divisor starting at index {} is zero", index)
+            }
+            ParseError::OtherVariable(lexem, variable) => write!(
+                f,
+                "'{:?}', found at index {}, is another variable than the unknown '{}'",
+                lexem,
+                lexem.get_index(),
+                variable
+            ),
+            ParseError::ImplicitMultiplication(lexem) => write!(
+                f,
+                "Implicit multiplication before '{:?}' at index {}, the strict mode requires a '*'",
+                lexem,
+                lexem.get_index()
+            ),
             ParseError::NoTokenProvided() => write!(f, "No token was provided"),
         }
     }
 }
 
-impl<'a> fmt::Debug for ParseError {
+impl fmt::Debug for ParseError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "ParseError: {}", self)
     }
 }
 
 impl Error for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::Lexer;
+
+    fn parse_with(
+        equation: &str,
+        variable: Option<char>,
+        strict: bool,
+    ) -> Result<Parser, Vec<ParseError>> {
+        let mut lexer = Lexer::new();
+        lexer.run(equation).unwrap();
+        let mut parser = Parser::new();
+        parser.run(lexer.get_lexems(), variable, strict)?;
+        Ok(parser)
+    }
+
+    fn parse(equation: &str) -> Parser {
+        parse_with(equation, None, false).ok().unwrap()
+    }
+
+    fn parse_errors(equation: &str) -> Vec<ParseError> {
+        parse_with(equation, None, false).err().unwrap()
+    }
+
+    fn span(expr: &Expr) -> (usize, usize) {
+        (expr.get_index(), expr.get_len())
+    }
+
+    #[test]
+    fn nested_groups() {
+        let parser = parse("((X + 1) * (2 - X)) = 0");
+        assert_eq!(
+            format!("{:?}", parser.get_equation().unwrap()),
+            "Equation(Group(Mult(Group(Add(X, Number(1))), Group(Sub(Number(2), X)))), Number(0))"
+        );
+        assert!(*parser.get_polynomial() == Polynomial::from_coeffs(&[2.0, 1.0, -1.0]));
+    }
+
+    #[test]
+    fn power_of_a_group_is_expanded() {
+        let parser = parse("(X + 1)^2 = 0");
+        assert!(*parser.get_polynomial() == Polynomial::from_coeffs(&[1.0, 2.0, 1.0]));
+        let parser = parse("2 * (X - 1)^3 = X^3");
+        assert!(*parser.get_polynomial() == Polynomial::from_coeffs(&[-2.0, 6.0, -6.0, 1.0]));
+    }
+
+    #[test]
+    fn spans_cover_the_source() {
+        let parser = parse("(X + 1)^2 = 3 * X");
+        let equation = parser.get_equation().unwrap();
+        assert_eq!(span(&equation.lhs), (0, 9));
+        match &equation.lhs {
+            Expr::Power { base, exponent, .. } => {
+                assert_eq!(*exponent, 2);
+                assert_eq!(span(base), (0, 7));
+                match base.as_ref() {
+                    Expr::Group { inner, .. } => assert_eq!(span(inner), (1, 5)),
+                    _ => panic!("the base is not a group"),
+                }
+            }
+            _ => panic!("the left-hand side is not a power"),
+        }
+        assert_eq!(span(&equation.rhs), (12, 5));
+        match &equation.rhs {
+            Expr::Mult { left, right, .. } => {
+                assert_eq!(span(left), (12, 1));
+                assert_eq!(span(right), (16, 1));
+            }
+            _ => panic!("the right-hand side is not a product"),
+        }
+    }
+
+    #[test]
+    fn no_error_on_end_after_recovery() {
+        let errs = parse_errors("=");
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].get_index(), Some(0));
+        let errs = parse_errors("X + 1");
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].get_index(), Some(5));
+    }
+
+    #[test]
+    fn division_by_a_number() {
+        let parser = parse("X/2 + 3/4 = 0");
+        assert!(*parser.get_polynomial() == Polynomial::from_coeffs(&[0.75, 0.5]));
+        assert!(parser.get_denominators().is_empty());
+        let parser = parse("X^2 / (1 + 3) = 1");
+        assert!(*parser.get_polynomial() == Polynomial::from_coeffs(&[-1.0, 0.0, 0.25]));
+        assert!(parser.get_denominators().is_empty());
+    }
+
+    #[test]
+    fn division_by_zero() {
+        let errs = parse_errors("X / 0 = 1");
+        assert_eq!(errs.len(), 1);
+        assert!(matches!(errs[0], ParseError::DivisionByZero(4, 1)));
+        let errs = parse_errors("1 / (X - X) = 1");
+        assert!(matches!(errs[0], ParseError::DivisionByZero(4, 7)));
+    }
+
+    #[test]
+    fn division_by_the_unknown() {
+        let parser = parse("1 / X = 2");
+        assert!(*parser.get_polynomial() == Polynomial::from_coeffs(&[1.0, -2.0]));
+        let denominators = parser.get_denominators();
+        assert_eq!(denominators.len(), 1);
+        assert_eq!(denominators[0].text, "X");
+        assert!(denominators[0].polynomial == Polynomial::monomial(1.0, 1));
+    }
+
+    #[test]
+    fn the_first_letter_is_the_unknown() {
+        let parser = parse("t^2 - 1 = 0");
+        assert_eq!(parser.get_variable(), 't');
+        assert!(*parser.get_polynomial() == Polynomial::from_coeffs(&[-1.0, 0.0, 1.0]));
+        assert_eq!(parse("x = 4 * x^2").get_variable(), 'x');
+        assert_eq!(parse("4 = 2").get_variable(), 'X');
+    }
+
+    #[test]
+    fn the_unknown_can_be_given() {
+        let parser = parse_with("y^2 = 4", Some('y'), false).ok().unwrap();
+        assert_eq!(parser.get_variable(), 'y');
+        assert!(*parser.get_polynomial() == Polynomial::from_coeffs(&[-4.0, 0.0, 1.0]));
+        let errs = parse_with("y^2 = 4", Some('z'), false).err().unwrap();
+        assert_eq!(errs.len(), 1);
+        assert!(matches!(errs[0], ParseError::OtherVariable(_, 'z')));
+        assert_eq!(errs[0].get_index(), Some(0));
+    }
+
+    #[test]
+    fn a_second_letter_is_an_error() {
+        let errs = parse_errors("x^2 + y = 0");
+        assert_eq!(errs.len(), 1);
+        assert!(matches!(errs[0], ParseError::OtherVariable(_, 'x')));
+        assert_eq!(errs[0].get_index(), Some(6));
+    }
+
+    #[test]
+    fn implicit_multiplication() {
+        let parser = parse("2(X + 1) = 0");
+        assert_eq!(
+            format!("{:?}", parser.get_equation().unwrap()),
+            "Equation(Mult(Number(2), Group(Add(X, Number(1)))), Number(0))"
+        );
+        let parser = parse("(X + 1)(X - 1) = 0");
+        assert!(*parser.get_polynomial() == Polynomial::from_coeffs(&[-1.0, 0.0, 1.0]));
+        let parser = parse("X X = 4");
+        assert!(*parser.get_polynomial() == Polynomial::from_coeffs(&[-4.0, 0.0, 1.0]));
+        let parser = parse("3/4X = 1");
+        assert!(*parser.get_polynomial() == Polynomial::from_coeffs(&[-1.0, 0.75]));
+    }
+
+    #[test]
+    fn a_number_after_a_factor_is_not_a_product() {
+        for (equation, index) in [("X 2 = 0", 2), ("(X + 1)2 = 0", 7)] {
+            let errs = parse_errors(equation);
+            assert_eq!(errs.len(), 1);
+            assert!(matches!(errs[0], ParseError::UnexpectedToken(_)));
+            assert_eq!(errs[0].get_index(), Some(index));
+        }
+    }
+
+    #[test]
+    fn strict_mode_rejects_implicit_multiplication() {
+        for (equation, index) in [("14X = 1", 2), ("2(X + 1) = 0", 1), ("X X = 4", 2)] {
+            let errs = parse_with(equation, None, true).err().unwrap();
+            assert_eq!(errs.len(), 1);
+            assert!(matches!(errs[0], ParseError::ImplicitMultiplication(_)));
+            assert_eq!(errs[0].get_index(), Some(index));
+        }
+        assert!(parse_with("14 * X = 1", None, true).is_ok());
+    }
+}
@@ -0,0 +1,292 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::args::Format;
+use crate::diagnostics;
+use crate::exact;
+use crate::exact::ExactSolver;
+use crate::json;
+use crate::lexer::{Lexer, LexicalError};
+use crate::parser::{Denominator, ParseError, Parser};
+use crate::solver;
+use crate::solver::{Solution, SolveError, Solver};
+
+pub struct Settings {
+    pub format: Format,
+    pub precision: usize,
+    pub exact: bool,
+    pub epsilon: f64,
+    pub variable: Option<char>,
+    pub strict: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            format: Format::Text,
+            precision: 2,
+            exact: false,
+            epsilon: 0.000000000001,
+            variable: None,
+            strict: false,
+        }
+    }
+}
+
+pub enum PipelineError {
+    // Along with the parse errors found in the lexems that follow the lexical errors
+    Lexical(Vec<LexicalError>, Vec<ParseError>),
+    Parse(Vec<ParseError>),
+    Solve(SolveError),
+}
+
+impl fmt::Display for PipelineError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let messages: Vec<String> = match self {
+            PipelineError::Lexical(errs, parse_errs) => errs
+                .iter()
+                .map(|err| format!("{:?}", err))
+                .chain(parse_errs.iter().map(|err| format!("{:?}", err)))
+                .collect(),
+            PipelineError::Parse(errs) => errs.iter().map(|err| format!("{:?}", err)).collect(),
+            PipelineError::Solve(err) => vec![format!("{:?}", err)],
+        };
+        write!(f, "{}", messages.join("\n"))
+    }
+}
+
+impl fmt::Debug for PipelineError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl Error for PipelineError {}
+
+fn solve_exact(
+    equation: &str,
+    parser: &Parser,
+    epsilon: f64,
+) -> Result<(Box<dyn Solver>, Solution), PipelineError> {
+    let ast = parser.get_equation().unwrap();
+    let reduction = exact::reduce(equation, ast).map_err(|err| PipelineError::Parse(vec![err]))?;
+    let exact_polynomial = reduction.polynomial;
+    let denominators: Vec<Denominator> = reduction
+        .denominators
+        .iter()
+        .map(|denominator| Denominator {
+            text: denominator.text.clone(),
+            polynomial: exact::to_float(&denominator.polynomial),
+            magnitude: exact::to_float(&denominator.magnitude),
+        })
+        .collect();
+    let polynomial = exact::to_float(&exact_polynomial);
+    let mut solver: Box<dyn Solver> = match exact_polynomial.degree() {
+        0..=2 => Box::new(ExactSolver::new(exact_polynomial)),
+        _ => solver::choose_solver(&polynomial),
+    };
+    let mut solution = solver
+        .solve(&polynomial, epsilon)
+        .map_err(PipelineError::Solve)?;
+    solution.reject(&denominators, epsilon);
+    Ok((solver, solution))
+}
+
+fn solve_float(
+    parser: &Parser,
+    epsilon: f64,
+) -> Result<(Box<dyn Solver>, Solution), PipelineError> {
+    let polynomial = parser
+        .get_polynomial()
+        .prune(parser.get_magnitude(), epsilon);
+    let denominators: Vec<Denominator> = parser
+        .get_denominators()
+        .iter()
+        .map(|denominator| Denominator {
+            text: denominator.text.clone(),
+            polynomial: denominator
+                .polynomial
+                .prune(&denominator.magnitude, epsilon),
+            magnitude: denominator.magnitude.clone(),
+        })
+        .collect();
+    let mut solver = solver::choose_solver(&polynomial);
+    let mut solution = solver
+        .solve(&polynomial, epsilon)
+        .map_err(PipelineError::Solve)?;
+    solution.reject(&denominators, epsilon);
+    Ok((solver, solution))
+}
+
+pub fn solve(
+    equation: &str,
+    settings: &Settings,
+) -> Result<(Box<dyn Solver>, Solution), PipelineError> {
+    let mut lexer = Lexer::new();
+    let lexical_errs = lexer.run(equation).err().unwrap_or_default();
+    let mut parser = Parser::new();
+    let parsed = parser.run(lexer.get_lexems(), settings.variable, settings.strict);
+    if !lexical_errs.is_empty() {
+        // The errors on the placeholders of the invalid lexems are already reported
+        let parse_errs = parsed
+            .err()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|err| {
+                !lexical_errs
+                    .iter()
+                    .any(|lexical_err| err.get_index() == Some(lexical_err.get_index()))
+            })
+            .collect();
+        return Err(PipelineError::Lexical(lexical_errs, parse_errs));
+    }
+    parsed.map_err(PipelineError::Parse)?;
+    let (solver, mut solution) = match settings.exact {
+        true => solve_exact(equation, &parser, settings.epsilon)?,
+        false => solve_float(&parser, settings.epsilon)?,
+    };
+    solution.variable = parser.get_variable();
+    Ok((solver, solution))
+}
+
+pub fn describe(solver: &dyn Solver, solution: &Solution, precision: usize) {
+    solver::describe_denominators(solution);
+    solver.describe(solution, precision);
+    solver::describe_rejections(solution, precision);
+}
+
+pub fn print_solution(solver: &dyn Solver, solution: &Solution, settings: &Settings) {
+    match settings.format {
+        Format::Text => describe(solver, solution, settings.precision),
+        Format::Json => println!("{}", json::solution(solution)),
+    }
+}
+
+pub fn render_error(equation: &str, err: &PipelineError, settings: &Settings) -> String {
+    match settings.format {
+        Format::Text => match err {
+            PipelineError::Lexical(errs, parse_errs) => match parse_errs.is_empty() {
+                true => diagnostics::lexical_errors(equation, errs),
+                false => format!(
+                    "{}\n{}",
+                    diagnostics::lexical_errors(equation, errs),
+                    diagnostics::parse_errors(equation, parse_errs)
+                ),
+            },
+            PipelineError::Parse(errs) => diagnostics::parse_errors(equation, errs),
+            PipelineError::Solve(err) => format!("Error: {:?}", err),
+        },
+        Format::Json => match err {
+            PipelineError::Lexical(errs, parse_errs) => json::lexical_errors(errs, parse_errs),
+            PipelineError::Parse(errs) => json::parse_errors(errs),
+            PipelineError::Solve(err) => json::solve_error(err),
+        },
+    }
+}
+
+pub fn run(equation: &str, settings: &Settings) -> Result<Solution, PipelineError> {
+    let (solver, solution) = solve(equation, settings)?;
+    print_solution(solver.as_ref(), &solution, settings);
+    Ok(solution)
+}
+
+pub fn report_error(equation: &str, err: &PipelineError, settings: &Settings) {
+    match settings.format {
+        Format::Text => eprintln!("{}", render_error(equation, err, settings)),
+        Format::Json => println!("{}", render_error(equation, err, settings)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::Roots;
+
+    #[test]
+    fn lexical_and_parse_errors_are_reported_together() {
+        match solve("X + $ = 1 + * 2", &Settings::default()) {
+            Err(PipelineError::Lexical(errs, parse_errs)) => {
+                assert_eq!(errs.len(), 1);
+                assert_eq!(errs[0].get_index(), 4);
+                assert_eq!(parse_errs.len(), 1);
+                assert_eq!(parse_errs[0].get_index(), Some(12));
+            }
+            _ => panic!("the errors are not reported"),
+        }
+    }
+
+    #[test]
+    fn placeholders_do_not_add_parse_errors() {
+        for equation in ["X² = 4", "2,5 X = 1", "X + $$ = 1", "1e999 X = 1"] {
+            match solve(equation, &Settings::default()) {
+                Err(PipelineError::Lexical(errs, parse_errs)) => {
+                    assert!(!errs.is_empty());
+                    assert!(parse_errs.is_empty(), "{}", equation);
+                }
+                _ => panic!("the lexical errors are not reported"),
+            }
+        }
+    }
+
+    #[test]
+    fn cancellation_noise_is_pruned() {
+        for epsilon in [1e-12, 1e-15] {
+            let settings = Settings {
+                epsilon,
+                ..Settings::default()
+            };
+            let (_, solution) = solve("0.1 + 0.2 = 0.3", &settings).ok().unwrap();
+            assert!(matches!(solution.roots, Roots::AllReals));
+            let (_, solution) = solve("0.1 * 3 * X^2 - 0.3 * X^2 + X = 1", &settings)
+                .ok()
+                .unwrap();
+            assert_eq!(solution.degree, 1);
+        }
+        let (_, solution) = solve("1e-17 * X^2 + X = 0", &Settings::default())
+            .ok()
+            .unwrap();
+        assert_eq!(solution.degree, 2);
+    }
+
+    fn real_roots(solution: &Solution) -> Vec<f64> {
+        match &solution.roots {
+            Roots::Roots(roots) => roots.iter().map(|root| root.value.real).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    #[test]
+    fn rational_equation() {
+        let (_, solution) = solve("1/X + 2 = 3/(X - 1)", &Settings::default())
+            .ok()
+            .unwrap();
+        assert_eq!(solution.degree, 2);
+        assert_eq!(solution.denominators, vec!["X", "(X - 1)"]);
+        assert!(solution.rejected.is_empty());
+        let roots = real_roots(&solution);
+        let half_sqrt_6 = 6.0_f64.sqrt() / 2.0;
+        assert_eq!(roots.len(), 2);
+        assert!((roots[0] - (1.0 - half_sqrt_6)).abs() < 1e-12);
+        assert!((roots[1] - (1.0 + half_sqrt_6)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn roots_of_a_denominator_are_rejected() {
+        for exact in [false, true] {
+            let settings = Settings {
+                exact,
+                ..Settings::default()
+            };
+            let (_, solution) = solve("X^2 / (X - 1) = 1 / (X - 1)", &settings)
+                .ok()
+                .unwrap();
+            assert_eq!(real_roots(&solution), vec![-1.0]);
+            assert_eq!(solution.rejected.len(), 1);
+            assert_eq!(solution.rejected[0].value.real, 1.0);
+            assert_eq!(solution.rejected[0].denominator, "(X - 1)");
+            let (_, solution) = solve("X / (X - 1) = 1 / (X - 1)", &settings).ok().unwrap();
+            assert!(matches!(solution.roots, Roots::None));
+            assert_eq!(solution.rejected.len(), 1);
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use std::io;
+use std::io::{BufRead, Write};
+
+use crate::args;
+use crate::args::Format;
+use crate::pipeline;
+use crate::pipeline::Settings;
+
+const HELP: &str = "Type an equation to solve it, or one of the following commands:
+:format text|json    choose the output format
+:precision N         print the solutions with N decimals
+:exact on|off        solve with exact rational arithmetic
+:epsilon E           treat values below E times their scale as zero
+:var NAME|auto       choose the unknown, or detect it
+:strict on|off       reject implicit multiplication such as `2X`
+:history             list the equations entered so far
+:help                print this help
+:quit                leave the session";
+
+enum Command {
+    Continue,
+    Quit,
+}
+
+fn meta_command(line: &str, settings: &mut Settings, history: &[String]) -> Command {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    match words.as_slice() {
+        [":quit"] | [":q"] => return Command::Quit,
+        [":format", "text"] => settings.format = Format::Text,
+        [":format", "json"] => settings.format = Format::Json,
+        [":exact", "on"] => settings.exact = true,
+        [":exact", "off"] => settings.exact = false,
+        [":precision", precision] => match precision.parse::<usize>() {
+            Ok(precision) => settings.precision = precision,
+            Err(_) => println!("'{}' is not a valid precision", precision),
+        },
+        [":epsilon", epsilon] => match args::parse_epsilon(epsilon) {
+            Ok(epsilon) => settings.epsilon = epsilon,
+            Err(err) => println!("{}", err),
+        },
+        [":strict", "on"] => settings.strict = true,
+        [":strict", "off"] => settings.strict = false,
+        [":var", "auto"] => settings.variable = None,
+        [":var", variable] => match args::parse_variable(variable) {
+            Ok(variable) => settings.variable = Some(variable),
+            Err(err) => println!("{}", err),
+        },
+        [":history"] => {
+            for (i, equation) in history.iter().enumerate() {
+                println!("{:>4}  {}", i + 1, equation);
+            }
+        }
+        [":help"] => println!("{}", HELP),
+        _ => println!("Unknown command '{}', type :help for help", line),
+    }
+    Command::Continue
+}
+
+pub fn run(mut settings: Settings) -> io::Result<()> {
+    let stdin = io::stdin();
+    let mut history: Vec<String> = Vec::new();
+    let mut line = String::new();
+
+    println!("computor_v1 interactive mode, type :help for help");
+    loop {
+        print!("> ");
+        io::stdout().flush()?;
+        line.clear();
+        if stdin.lock().read_line(&mut line)? == 0 {
+            println!();
+            return Ok(());
+        }
+        let input = line.trim();
+        if input.is_empty() {
+            continue;
+        }
+        if input.starts_with(':') {
+            match meta_command(input, &mut settings, &history) {
+                Command::Continue => continue,
+                Command::Quit => return Ok(()),
+            }
+        }
+        history.push(String::from(input));
+        if let Err(err) = pipeline::run(input, &settings) {
+            pipeline::report_error(input, &err, &settings);
+        }
+    }
+}
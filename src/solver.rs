@@ -1,34 +1,252 @@
-use std::collections::HashMap;
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt;
 
+use crate::factor;
+use crate::factor::Factorisation;
 use crate::maths;
-use crate::maths::Complex;
+use crate::maths::{Complex, Polynomial, Scaled, Surd};
+use crate::parser::Denominator;
+
+const REFINE_ITERATIONS: usize = 8;
+const REFINE_BOUND: f64 = 0.001;
+const POLE_THRESHOLD: f64 = 0.000000001;
 
 pub trait Solver {
-    fn solve(&mut self, degrees: &HashMap<u32, f64>);
-    fn describe(&self);
+    fn solve(&mut self, polynomial: &Polynomial, epsilon: f64) -> Result<Solution, SolveError>;
+    fn describe(&self, solution: &Solution, precision: usize);
+}
+
+pub struct Solution {
+    pub polynomial: Polynomial,
+    pub degree: u32,
+    pub discriminant: Option<Scaled>,
+    pub roots: Roots,
+    pub denominators: Vec<String>,
+    pub rejected: Vec<Rejection>,
+    pub variable: char,
+    pub convergence: Option<Convergence>,
+    pub factorisation: Option<Factorisation>,
+}
+
+// Reported by the iterative method, with |P(z)| for each root
+pub struct Convergence {
+    pub iterations: usize,
+    pub residuals: Vec<f64>,
+}
+
+pub enum SolveError {
+    NotFinite,
+    NoConvergence { iterations: usize, residual: f64 },
+    TooBigDegree(u32),
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SolveError::NotFinite => write!(
+                f,
+                "The intermediate values overflow the floating point range"
+            ),
+            SolveError::NoConvergence {
+                iterations,
+                residual,
+            } => write!(
+                f,
+                "The Durand-Kerner method did not converge after {} iterations, the largest residual is {:.2e}",
+                iterations, residual
+            ),
+            SolveError::TooBigDegree(degree) => write!(
+                f,
+                "The polynomial degree {} is above {}, the highest degree the Durand-Kerner method is run on",
+                degree,
+                MoreDegreeSolver::MAX_DEGREE
+            ),
+        }
+    }
+}
+
+impl fmt::Debug for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "SolveError: {}", self)
+    }
+}
+
+impl Error for SolveError {}
+
+pub struct Rejection {
+    pub value: Complex,
+    pub denominator: String,
+}
+
+pub enum Roots {
+    None,
+    AllReals,
+    Roots(Vec<Root>),
+}
+
+#[derive(Clone)]
+pub struct Root {
+    pub value: Complex,
+    pub multiplicity: usize,
+    pub kind: RootKind,
+}
+
+#[derive(Copy, Clone, PartialEq)]
+pub enum RootKind {
+    Real,
+    Complex,
+}
+
+impl Root {
+    pub fn new(value: Complex, multiplicity: usize) -> Self {
+        Root {
+            value,
+            multiplicity,
+            kind: if value.imag == 0.0 {
+                RootKind::Real
+            } else {
+                RootKind::Complex
+            },
+        }
+    }
+}
+
+impl Solution {
+    fn new(
+        polynomial: &Polynomial,
+        degree: u32,
+        discriminant: Option<Scaled>,
+        roots: Roots,
+        factorisation: Option<Factorisation>,
+    ) -> Self {
+        Solution {
+            polynomial: polynomial.clone(),
+            degree,
+            discriminant,
+            roots,
+            denominators: Vec::new(),
+            rejected: Vec::new(),
+            variable: 'X',
+            convergence: None,
+            factorisation,
+        }
+    }
+
+    pub fn reject(&mut self, denominators: &[Denominator], epsilon: f64) {
+        for denominator in denominators.iter() {
+            if !self.denominators.contains(&denominator.text) {
+                self.denominators.push(denominator.text.clone());
+            }
+        }
+        match std::mem::replace(&mut self.roots, Roots::None) {
+            Roots::Roots(roots) => {
+                let mut kept = Vec::new();
+                for root in roots.into_iter() {
+                    match denominators
+                        .iter()
+                        .find(|d| is_pole(&d.polynomial, &root.value))
+                    {
+                        Some(denominator) => self.rejected.push(Rejection {
+                            value: root.value,
+                            denominator: denominator.text.clone(),
+                        }),
+                        None => kept.push(root),
+                    }
+                }
+                if !kept.is_empty() {
+                    self.roots = Roots::Roots(kept);
+                }
+            }
+            Roots::AllReals => {
+                for denominator in denominators.iter() {
+                    let polynomial = &denominator.polynomial;
+                    let solution = choose_solver(polynomial).solve(polynomial, epsilon);
+                    if let Ok(Solution {
+                        roots: Roots::Roots(roots),
+                        ..
+                    }) = solution
+                    {
+                        for root in roots.iter().filter(|root| root.kind == RootKind::Real) {
+                            if !self
+                                .rejected
+                                .iter()
+                                .any(|other| other.value.approx_eq(&root.value, POLE_THRESHOLD))
+                            {
+                                self.rejected.push(Rejection {
+                                    value: root.value,
+                                    denominator: denominator.text.clone(),
+                                });
+                            }
+                        }
+                    }
+                }
+                self.roots = Roots::AllReals;
+            }
+            Roots::None => (),
+        }
+    }
+}
+
+fn is_pole(denominator: &Polynomial, z: &Complex) -> bool {
+    let bound = denominator.map(|coeff| coeff.abs()).evaluate(&z.abs());
+    denominator.evaluate_complex(z).abs() <= POLE_THRESHOLD * bound
+}
+
+impl fmt::Display for Root {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let precision = f.precision().unwrap_or(2);
+        write!(
+            f,
+            "{:.*}{}",
+            precision,
+            self.value,
+            format_multiplicity(self.multiplicity)
+        )
+    }
 }
 
 pub struct ZeroDegreeSolver {
+    polynomial: Polynomial,
     degree_0: f64,
 }
 
+impl Default for ZeroDegreeSolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ZeroDegreeSolver {
     pub fn new() -> Self {
-        ZeroDegreeSolver { degree_0: 0.0 }
+        ZeroDegreeSolver {
+            polynomial: Polynomial::new(),
+            degree_0: 0.0,
+        }
     }
 }
 
 impl Solver for ZeroDegreeSolver {
-    fn solve(&mut self, degrees: &HashMap<u32, f64>) {
-        self.degree_0 = *degrees.get(&0).unwrap_or_else(|| &0.0);
+    fn solve(&mut self, polynomial: &Polynomial, _epsilon: f64) -> Result<Solution, SolveError> {
+        self.polynomial = polynomial.clone();
+        self.degree_0 = polynomial.coeff(0);
+        let roots = match self.degree_0 == 0.0 {
+            true => Roots::AllReals,
+            false => Roots::None,
+        };
+        Ok(Solution::new(
+            polynomial,
+            0,
+            None,
+            roots,
+            factor::factorise_float(polynomial),
+        ))
     }
 
-    fn describe(&self) {
-        println!(
-            "Reduced form: {}{} * X^0 = 0",
-            if self.degree_0 < 0.0 { "- " } else { "" },
-            self.degree_0.abs()
-        );
+    fn describe(&self, solution: &Solution, _precision: usize) {
+        let variable = solution.variable;
+        println!("Reduced form: {} = 0", self.polynomial.named(variable));
+        describe_factored_form(solution);
         println!("Polynomial degree: 0");
         if self.degree_0 == 0.0 {
             println!("All real numbers are solution");
@@ -39,14 +257,22 @@ impl Solver for ZeroDegreeSolver {
 }
 
 pub struct OneDegreeSolver {
+    polynomial: Polynomial,
     degree_0: f64,
     degree_1: f64,
     x: f64,
 }
 
+impl Default for OneDegreeSolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl OneDegreeSolver {
     pub fn new() -> Self {
         OneDegreeSolver {
+            polynomial: Polynomial::new(),
             degree_0: 0.0,
             degree_1: 0.0,
             x: 0.0,
@@ -55,39 +281,55 @@ impl OneDegreeSolver {
 }
 
 impl Solver for OneDegreeSolver {
-    fn solve(&mut self, degrees: &HashMap<u32, f64>) {
-        self.degree_0 = *degrees.get(&0).unwrap_or_else(|| &0.0);
-        self.degree_1 = *degrees.get(&1).unwrap();
+    fn solve(&mut self, polynomial: &Polynomial, _epsilon: f64) -> Result<Solution, SolveError> {
+        self.polynomial = polynomial.clone();
+        self.degree_0 = polynomial.coeff(0);
+        self.degree_1 = polynomial.coeff(1);
         self.x = -(self.degree_0 / self.degree_1);
+        let root = Complex {
+            real: self.x,
+            imag: 0.0,
+        };
+        Ok(Solution::new(
+            polynomial,
+            1,
+            None,
+            Roots::Roots(vec![Root::new(root, 1)]),
+            factor::factorise_float(polynomial),
+        ))
     }
 
-    fn describe(&self) {
-        println!(
-            "Reduced form: {}{} * X^0 {} {} * X^1 = 0",
-            if self.degree_0 < 0.0 { "- " } else { "" },
-            self.degree_0.abs(),
-            if self.degree_1 < 0.0 { "-" } else { "+" },
-            self.degree_1.abs()
-        );
+    fn describe(&self, solution: &Solution, precision: usize) {
+        let variable = solution.variable;
+        println!("Reduced form: {} = 0", self.polynomial.named(variable));
+        describe_factored_form(solution);
         println!("Polynomial degree: 1");
         println!("a = {}, b = {}", self.degree_1, self.degree_0);
         println!("The solution is:");
-        println!("-b / a = {:.2}", self.x);
+        println!("-b / a = {:.*}", precision, self.x);
     }
 }
 
 pub struct TwoDegreeSolver {
+    polynomial: Polynomial,
     degree_0: f64,
     degree_1: f64,
     degree_2: f64,
     z_1: Complex,
     z_2: Complex,
-    delta: f64,
+    delta: Scaled,
+}
+
+impl Default for TwoDegreeSolver {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl TwoDegreeSolver {
     pub fn new() -> Self {
         TwoDegreeSolver {
+            polynomial: Polynomial::new(),
             degree_0: 0.0,
             degree_1: 0.0,
             degree_2: 0.0,
@@ -99,129 +341,1311 @@ impl TwoDegreeSolver {
                 real: 0.0,
                 imag: 0.0,
             },
-            delta: 0.0,
+            delta: Scaled::new(0.0, 0),
         }
     }
 }
 
+impl TwoDegreeSolver {
+    fn closed_form(&self) -> Option<(Surd, Surd)> {
+        Some(maths::quadratic_roots(
+            &maths::to_rational(self.degree_2)?,
+            &maths::to_rational(self.degree_1)?,
+            &maths::to_rational(self.degree_0)?,
+        ))
+    }
+}
+
 impl Solver for TwoDegreeSolver {
-    fn solve(&mut self, degrees: &HashMap<u32, f64>) {
-        self.degree_0 = *degrees.get(&0).unwrap_or_else(|| &0.0);
-        self.degree_1 = *degrees.get(&1).unwrap_or_else(|| &0.0);
-        self.degree_2 = *degrees.get(&2).unwrap();
-        self.delta = self.degree_1 * self.degree_1 - 4.0 * self.degree_2 * self.degree_0;
-        if self.delta == 0.0 {
-            self.z_1.real = -self.degree_1 / (2.0 * self.degree_2);
-        } else if self.delta > 0.0 {
-            let sqrt_delta = maths::sqrt(self.delta);
-            self.z_1.real = (-self.degree_1 - sqrt_delta) / (2.0 * self.degree_2);
-            self.z_2.real = (-self.degree_1 + sqrt_delta) / (2.0 * self.degree_2);
+    fn solve(&mut self, polynomial: &Polynomial, epsilon: f64) -> Result<Solution, SolveError> {
+        self.polynomial = polynomial.clone();
+        self.degree_0 = polynomial.coeff(0);
+        self.degree_1 = polynomial.coeff(1);
+        self.degree_2 = polynomial.coeff(2);
+        // Scaling by a power of two keeps b^2 from overflowing without changing the roots
+        let exponent = maths::binary_exponent(&[self.degree_2, self.degree_1, self.degree_0]);
+        let scale = maths::power_of_two(exponent);
+        let (a, b, c) = (
+            self.degree_2 / scale,
+            self.degree_1 / scale,
+            self.degree_0 / scale,
+        );
+        let factorisation = factor::factorise_float(polynomial);
+        let delta = match highest_multiplicity(factorisation.as_ref()) {
+            Some(1) => b * b - 4.0 * a * c,
+            Some(_) => 0.0,
+            None => maths::chop(b * b - 4.0 * a * c, &[b * b, 4.0 * a * c], epsilon),
+        };
+        self.delta = Scaled::new(delta, 2 * exponent);
+        if delta == 0.0 {
+            self.z_1 = Complex::new(-b / (2.0 * a), 0.0);
+            self.z_2 = self.z_1;
+        } else if delta > 0.0 {
+            // q = -(b + sign(b)√Δ) / 2 never subtracts close numbers, the roots are q / a and c / q
+            let sqrt_delta = maths::sqrt(delta).unwrap_or(f64::NAN);
+            let q = -(b + sqrt_delta.copysign(b)) / 2.0;
+            let (z_1, z_2) = match b.is_sign_negative() {
+                true => (c / q, q / a),
+                false => (q / a, c / q),
+            };
+            self.z_1 = Complex::new(z_1, 0.0);
+            self.z_2 = Complex::new(z_2, 0.0);
         } else {
-            let sqrt_delta = maths::sqrt(-self.delta);
-            self.z_1.real = -self.degree_1 / (2.0 * self.degree_2);
-            self.z_1.imag = -sqrt_delta / (2.0 * self.degree_2);
-            self.z_2.real = -self.degree_1 / (2.0 * self.degree_2);
-            self.z_2.imag = sqrt_delta / (2.0 * self.degree_2);
+            let sqrt_delta = maths::sqrt(-delta).unwrap_or(f64::NAN);
+            self.z_1 = Complex::new(-b / (2.0 * a), -sqrt_delta / (2.0 * a));
+            self.z_2 = self.z_1.conj();
         }
+        let roots = group_roots(
+            polynomial,
+            factorisation.as_ref(),
+            &[self.z_1, self.z_2],
+            epsilon,
+        );
+        Ok(Solution::new(
+            polynomial,
+            2,
+            Some(self.delta),
+            Roots::Roots(roots),
+            factorisation,
+        ))
     }
 
-    fn describe(&self) {
+    fn describe(&self, solution: &Solution, precision: usize) {
+        let variable = solution.variable;
+        println!("Reduced form: {} = 0", self.polynomial.named(variable));
+        describe_factored_form(solution);
+        println!("Polynomial degree: 2");
         println!(
-            "Reduced form: {}{} * X^0 {} {} * X^1 {} {} * X^2 = 0",
-            if self.degree_0 < 0.0 { "- " } else { "" },
-            self.degree_0.abs(),
-            if self.degree_1 < 0.0 { "-" } else { "+" },
-            self.degree_1.abs(),
-            if self.degree_2 < 0.0 { "-" } else { "+" },
-            self.degree_2.abs()
+            "a = {}, b = {}, c = {}",
+            self.degree_2, self.degree_1, self.degree_0
         );
-        println!("Polynomial degree: 2");
-        println!("a = {}, b = {}, c = {}", self.degree_2, self.degree_1, self.degree_0);
         println!("Δ = b^2 - 4ac = {}", self.delta);
-        if self.delta == 0.0 {
+        let closed_form = self.closed_form();
+        let (z_1, z_2) = match &closed_form {
+            Some((z_1, z_2)) => (format_surd(z_1, precision), format_surd(z_2, precision)),
+            None => (
+                format!("{:.*}", precision, self.z_1),
+                format!("{:.*}", precision, self.z_2),
+            ),
+        };
+        if self.delta.value == 0.0 {
             println!("Discriminant is zero, the solution is:");
-            println!("-b / 2a = {:.2}", self.z_1.real);
-        } else if self.delta > 0.0 {
+            println!("-b / 2a = {}{}", z_1, format_multiplicity(2));
+        } else if self.delta.value > 0.0 {
             println!("Discriminant is strictly positive, the two solutions are:");
-            println!("(-b - √Δ) / 2a) = {:.2}", self.z_1.real);
-            println!("(-b + √Δ) / 2a) = {:.2}", self.z_2.real);
+            println!("(-b - √Δ) / 2a = {}", z_1);
+            println!("(-b + √Δ) / 2a = {}", z_2);
         } else {
             println!("Discriminant is strictly negative, the two complex solutions are:");
+            println!("(-b - i√(-Δ)) / 2a = {}", z_1);
+            println!("(-b + i√(-Δ)) / 2a = {}", z_2);
+        }
+    }
+}
+
+pub struct ThreeDegreeSolver {
+    polynomial: Polynomial,
+    degree_0: f64,
+    degree_1: f64,
+    degree_2: f64,
+    degree_3: f64,
+    p: f64,
+    q: f64,
+    delta: Scaled,
+    z: [Complex; 3],
+    fallback: Option<MoreDegreeSolver>,
+}
+
+impl Default for ThreeDegreeSolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ThreeDegreeSolver {
+    pub fn new() -> Self {
+        ThreeDegreeSolver {
+            polynomial: Polynomial::new(),
+            degree_0: 0.0,
+            degree_1: 0.0,
+            degree_2: 0.0,
+            degree_3: 0.0,
+            p: 0.0,
+            q: 0.0,
+            delta: Scaled::new(0.0, 0),
+            z: [Complex {
+                real: 0.0,
+                imag: 0.0,
+            }; 3],
+            fallback: None,
+        }
+    }
+}
+
+impl ThreeDegreeSolver {
+    // When p or q overflow, the roots are still approximated with the Durand-Kerner method
+    fn fall_back(&mut self, polynomial: &Polynomial, epsilon: f64) -> Result<Solution, SolveError> {
+        let mut fallback = MoreDegreeSolver::new();
+        let solution = fallback.solve(polynomial, epsilon)?;
+        if let Roots::Roots(roots) = &solution.roots {
+            let values = roots
+                .iter()
+                .flat_map(|root| std::iter::repeat_n(root.value, root.multiplicity));
+            for (z, value) in self.z.iter_mut().zip(values) {
+                *z = value;
+            }
+        }
+        self.fallback = Some(fallback);
+        Ok(solution)
+    }
+}
+
+impl Solver for ThreeDegreeSolver {
+    fn solve(&mut self, polynomial: &Polynomial, epsilon: f64) -> Result<Solution, SolveError> {
+        self.polynomial = polynomial.clone();
+        self.degree_0 = polynomial.coeff(0);
+        self.degree_1 = polynomial.coeff(1);
+        self.degree_2 = polynomial.coeff(2);
+        self.degree_3 = polynomial.coeff(3);
+        // p and q do not change when the coefficients are scaled, Δ is multiplied by scale^4
+        let exponent =
+            maths::binary_exponent(&[self.degree_3, self.degree_2, self.degree_1, self.degree_0]);
+        let scale = maths::power_of_two(exponent);
+        let (a, b, c, d) = (
+            self.degree_3 / scale,
+            self.degree_2 / scale,
+            self.degree_1 / scale,
+            self.degree_0 / scale,
+        );
+        self.fallback = None;
+        let p_terms = [3.0 * a * c, -b * b];
+        let q_terms = [2.0 * b * b * b, -9.0 * a * b * c, 27.0 * a * a * d];
+        let p = p_terms.iter().sum::<f64>() / (3.0 * a * a);
+        let q = q_terms.iter().sum::<f64>() / (27.0 * a * a * a);
+        if !p.is_finite() || !q.is_finite() {
+            return self.fall_back(polynomial, epsilon);
+        }
+        // Δ is zero for a repeated root, p and q for a triple one
+        let factorisation = factor::factorise_float(polynomial);
+        let multiplicity = highest_multiplicity(factorisation.as_ref());
+        let chop = |terms: &[f64], zero: bool| match multiplicity {
+            Some(_) if zero => 0.0,
+            Some(_) => terms.iter().sum(),
+            None => maths::chop(terms.iter().sum(), terms, epsilon),
+        };
+        let terms = [
+            18.0 * a * b * c * d,
+            -4.0 * b * b * b * d,
+            b * b * c * c,
+            -4.0 * a * c * c * c,
+            -27.0 * a * a * d * d,
+        ];
+        self.delta = Scaled::new(chop(&terms, multiplicity > Some(1)), 4 * exponent);
+        self.p = chop(&p_terms, multiplicity == Some(3)) / (3.0 * a * a);
+        self.q = chop(&q_terms, multiplicity == Some(3)) / (27.0 * a * a * a);
+        let shift = -b / (3.0 * a);
+        self.z = [Complex {
+            real: shift,
+            imag: 0.0,
+        }; 3];
+        if self.delta.value == 0.0 {
+            if self.p != 0.0 {
+                self.z[0].real += 3.0 * self.q / self.p;
+                self.z[1].real += -3.0 * self.q / (2.0 * self.p);
+                self.z[2].real = self.z[1].real;
+            }
+        } else if self.delta.value > 0.0 {
+            let m = 2.0 * maths::sqrt(-self.p / 3.0).unwrap_or(f64::NAN);
+            let cos_arg = (3.0 * self.q / (self.p * m)).clamp(-1.0, 1.0);
+            let theta = cos_arg.acos() / 3.0;
+            for (k, z) in self.z.iter_mut().enumerate() {
+                z.real += m * (theta - 2.0 * std::f64::consts::PI * k as f64 / 3.0).cos();
+            }
+        } else {
+            let sqrt_s = maths::sqrt(self.q * self.q / 4.0 + self.p * self.p * self.p / 27.0)
+                .unwrap_or(f64::NAN);
+            let u = maths::cbrt(-self.q / 2.0 + sqrt_s);
+            let v = maths::cbrt(-self.q / 2.0 - sqrt_s);
+            self.z[0].real += u + v;
+            self.z[1].real += -(u + v) / 2.0;
+            self.z[1].imag = -maths::sqrt(3.0).unwrap_or(f64::NAN) / 2.0 * (u - v);
+            self.z[2].real = self.z[1].real;
+            self.z[2].imag = -self.z[1].imag;
+        }
+        let roots = group_roots(polynomial, factorisation.as_ref(), &self.z, epsilon);
+        // When p is small, 3q / p is less accurate than the grouped roots
+        if self.delta.value == 0.0 {
+            for root in roots.iter() {
+                match root.multiplicity {
+                    1 => self.z[0] = root.value,
+                    2 => self.z[1] = root.value,
+                    _ => self.z = [root.value; 3],
+                }
+            }
+            self.z[2] = self.z[1];
+        }
+        Ok(Solution::new(
+            polynomial,
+            3,
+            Some(self.delta),
+            Roots::Roots(roots),
+            factorisation,
+        ))
+    }
+
+    fn describe(&self, solution: &Solution, precision: usize) {
+        if let Some(fallback) = &self.fallback {
+            return fallback.describe(solution, precision);
+        }
+        let variable = solution.variable;
+        println!("Reduced form: {} = 0", self.polynomial.named(variable));
+        describe_factored_form(solution);
+        println!("Polynomial degree: 3");
+        println!(
+            "a = {}, b = {}, c = {}, d = {}",
+            self.degree_3, self.degree_2, self.degree_1, self.degree_0
+        );
+        println!(
+            "Δ = 18abcd - 4b^3d + b^2c^2 - 4ac^3 - 27a^2d^2 = {}",
+            self.delta
+        );
+        println!(
+            "Substituting {} = t - b / 3a gives the depressed cubic t^3 + pt + q = 0 with:",
+            variable
+        );
+        println!("p = (3ac - b^2) / 3a^2 = {}", self.p);
+        println!("q = (2b^3 - 9abc + 27a^2d) / 27a^3 = {}", self.q);
+        if self.delta.value == 0.0 && self.p == 0.0 {
+            println!("Discriminant and p are zero, the triple solution is:");
+            println!(
+                "-b / 3a = {:.*}{}",
+                precision,
+                self.z[0],
+                format_multiplicity(3)
+            );
+        } else if self.delta.value == 0.0 {
+            println!("Discriminant is zero, the simple and the double solutions are:");
+            println!("3q / p - b / 3a = {:.*}", precision, self.z[0]);
+            println!(
+                "-3q / 2p - b / 3a = {:.*}{}",
+                precision,
+                self.z[1],
+                format_multiplicity(2)
+            );
+        } else if self.delta.value > 0.0 {
+            println!(
+                "Discriminant is strictly positive, the three real solutions are (k = 0, 1, 2):"
+            );
+            println!("2√(-p/3) * cos(arccos(3q / 2p * √(-3/p)) / 3 - 2kπ / 3) - b / 3a =");
+            for z in self.z.iter() {
+                println!("{:.*}", precision, z);
+            }
+        } else {
             println!(
-                "(-b - i√(-Δ)) / 2a = {:.2} {} {:.2} * i",
-                self.z_1.real,
-                if self.z_1.imag >= 0.0 { "+" } else { "-" },
-                self.z_1.imag.abs()
+                "Discriminant is strictly negative, the real and the two complex solutions are:"
             );
+            println!("u = ∛(-q/2 + √(q^2/4 + p^3/27)), v = ∛(-q/2 - √(q^2/4 + p^3/27))");
+            println!("u + v - b / 3a = {:.*}", precision, self.z[0]);
             println!(
-                "(-b + i√(-Δ)) / 2a = {:.2} {} {:.2} * i",
-                self.z_2.real,
-                if self.z_2.imag >= 0.0 { "+" } else { "-" },
-                self.z_2.imag.abs()
+                "-(u + v) / 2 - b / 3a - i√3(u - v) / 2 = {:.*}",
+                precision, self.z[1]
             );
+            println!(
+                "-(u + v) / 2 - b / 3a + i√3(u - v) / 2 = {:.*}",
+                precision, self.z[2]
+            );
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq)]
+enum QuarticCase {
+    Biquadratic,
+    DepressedBiquadratic,
+    Ferrari,
+}
+
+pub struct FourDegreeSolver {
+    polynomial: Polynomial,
+    degree_0: f64,
+    degree_1: f64,
+    degree_2: f64,
+    degree_3: f64,
+    degree_4: f64,
+    case: QuarticCase,
+    p: f64,
+    q: f64,
+    r: f64,
+    m: f64,
+    squares: [Complex; 2],
+    roots: Vec<Root>,
+    fallback: Option<MoreDegreeSolver>,
+}
+
+impl Default for FourDegreeSolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FourDegreeSolver {
+    pub fn new() -> Self {
+        FourDegreeSolver {
+            polynomial: Polynomial::new(),
+            degree_0: 0.0,
+            degree_1: 0.0,
+            degree_2: 0.0,
+            degree_3: 0.0,
+            degree_4: 0.0,
+            case: QuarticCase::Ferrari,
+            p: 0.0,
+            q: 0.0,
+            r: 0.0,
+            m: 0.0,
+            squares: [Complex {
+                real: 0.0,
+                imag: 0.0,
+            }; 2],
+            roots: Vec::new(),
+            fallback: None,
         }
     }
+
+    fn solve_squares(&mut self, a: f64, b: f64, c: f64, shift: f64, epsilon: f64) -> Vec<Complex> {
+        let delta = maths::chop(b * b - 4.0 * a * c, &[b * b, 4.0 * a * c], epsilon);
+        let sqrt_delta = maths::sqrt(delta.abs()).unwrap_or(f64::NAN);
+        for (i, sign) in [-1.0, 1.0].iter().enumerate() {
+            self.squares[i] = if delta >= 0.0 {
+                Complex::new((-b + sign * sqrt_delta) / (2.0 * a), 0.0)
+            } else {
+                Complex::new(-b / (2.0 * a), sign * sqrt_delta / (2.0 * a))
+            };
+        }
+        let mut roots = Vec::new();
+        for square in self.squares.iter() {
+            let root = square.sqrt();
+            for sign in [-1.0, 1.0].iter() {
+                roots.push(*sign * root + shift);
+            }
+        }
+        roots
+    }
+
+    fn solve_ferrari(&mut self, shift: f64, epsilon: f64) -> Result<Vec<Complex>, SolveError> {
+        let resolvent = Polynomial::from_coeffs(&[
+            -self.q * self.q,
+            2.0 * self.p * self.p - 8.0 * self.r,
+            8.0 * self.p,
+            8.0,
+        ]);
+        let mut cubic_solver = ThreeDegreeSolver::new();
+        cubic_solver.solve(&resolvent, epsilon)?;
+        self.m = cubic_solver
+            .z
+            .iter()
+            .filter(|z| z.imag == 0.0)
+            .map(|z| z.real)
+            .fold(0.0, f64::max);
+        let sqrt_2m = maths::sqrt(2.0 * self.m).unwrap_or(f64::NAN);
+        let mut roots = Vec::new();
+        for s_1 in [-1.0, 1.0].iter() {
+            let terms = [2.0 * self.p, 2.0 * self.m, s_1 * 2.0 * self.q / sqrt_2m];
+            let inner = -maths::chop(terms.iter().sum(), &terms, epsilon);
+            let sqrt_inner = maths::sqrt(inner.abs()).unwrap_or(f64::NAN);
+            for s_2 in [-1.0, 1.0].iter() {
+                roots.push(if inner >= 0.0 {
+                    Complex::new((s_1 * sqrt_2m + s_2 * sqrt_inner) / 2.0 + shift, 0.0)
+                } else {
+                    Complex::new(s_1 * sqrt_2m / 2.0 + shift, s_2 * sqrt_inner / 2.0)
+                });
+            }
+        }
+        Ok(roots)
+    }
+}
+
+impl Solver for FourDegreeSolver {
+    fn solve(&mut self, polynomial: &Polynomial, epsilon: f64) -> Result<Solution, SolveError> {
+        self.polynomial = polynomial.clone();
+        self.degree_0 = polynomial.coeff(0);
+        self.degree_1 = polynomial.coeff(1);
+        self.degree_2 = polynomial.coeff(2);
+        self.degree_3 = polynomial.coeff(3);
+        self.degree_4 = polynomial.coeff(4);
+        self.fallback = None;
+        // The roots, p, q and r do not change when the coefficients are scaled
+        let scale = maths::binary_scale(&[
+            self.degree_4,
+            self.degree_3,
+            self.degree_2,
+            self.degree_1,
+            self.degree_0,
+        ]);
+        let (a, b, c, d, e) = (
+            self.degree_4 / scale,
+            self.degree_3 / scale,
+            self.degree_2 / scale,
+            self.degree_1 / scale,
+            self.degree_0 / scale,
+        );
+        let roots = if b == 0.0 && d == 0.0 {
+            self.case = QuarticCase::Biquadratic;
+            self.solve_squares(a, c, e, 0.0, epsilon)
+        } else {
+            self.p = (8.0 * a * c - 3.0 * b * b) / (8.0 * a * a);
+            let terms = [b * b * b, -4.0 * a * b * c, 8.0 * a * a * d];
+            self.q = maths::chop(terms.iter().sum(), &terms, epsilon) / (8.0 * a * a * a);
+            self.r = (-3.0 * b * b * b * b + 256.0 * a * a * a * e - 64.0 * a * a * b * d
+                + 16.0 * a * b * b * c)
+                / (256.0 * a * a * a * a);
+            let shift = -b / (4.0 * a);
+            if !self.p.is_finite() || !self.q.is_finite() || !self.r.is_finite() {
+                // The roots are still approximated with the Durand-Kerner method
+                let mut fallback = MoreDegreeSolver::new();
+                let solution = fallback.solve(polynomial, epsilon)?;
+                self.fallback = Some(fallback);
+                return Ok(solution);
+            }
+            if self.q == 0.0 {
+                self.case = QuarticCase::DepressedBiquadratic;
+                self.solve_squares(1.0, self.p, self.r, shift, epsilon)
+            } else {
+                self.case = QuarticCase::Ferrari;
+                self.solve_ferrari(shift, epsilon)?
+            }
+        };
+        let factorisation = factor::factorise_float(polynomial);
+        self.roots = group_roots(polynomial, factorisation.as_ref(), &roots, epsilon);
+        Ok(Solution::new(
+            polynomial,
+            4,
+            None,
+            Roots::Roots(self.roots.clone()),
+            factorisation,
+        ))
+    }
+
+    fn describe(&self, solution: &Solution, precision: usize) {
+        if let Some(fallback) = &self.fallback {
+            return fallback.describe(solution, precision);
+        }
+        let variable = solution.variable;
+        println!("Reduced form: {} = 0", self.polynomial.named(variable));
+        describe_factored_form(solution);
+        println!("Polynomial degree: 4");
+        println!(
+            "a = {}, b = {}, c = {}, d = {}, e = {}",
+            self.degree_4, self.degree_3, self.degree_2, self.degree_1, self.degree_0
+        );
+        if self.case == QuarticCase::Biquadratic {
+            println!("b and d are zero, the equation is biquadratic.");
+            println!(
+                "Substituting Y = {}^2 gives aY^2 + cY + e = 0 with the solutions:",
+                variable
+            );
+        } else {
+            println!("Substituting {} = y - b / 4a gives the depressed quartic y^4 + py^2 + qy + r = 0 with:", variable);
+            println!("p = (8ac - 3b^2) / 8a^2 = {}", self.p);
+            println!("q = (b^3 - 4abc + 8a^2d) / 8a^3 = {}", self.q);
+            println!(
+                "r = (-3b^4 + 256a^3e - 64a^2bd + 16ab^2c) / 256a^4 = {}",
+                self.r
+            );
+        }
+        match self.case {
+            QuarticCase::Biquadratic => (),
+            QuarticCase::DepressedBiquadratic => {
+                println!("q is zero, the depressed quartic is biquadratic.");
+                println!("Substituting Y = y^2 gives Y^2 + pY + r = 0 with the solutions:");
+            }
+            QuarticCase::Ferrari => {
+                println!("The resolvent cubic 8m^3 + 8pm^2 + (2p^2 - 8r)m - q^2 = 0 has the positive solution:");
+                println!("m = {:.*}", precision, self.m);
+                println!("The solutions are given by:");
+                println!("(±√(2m) ± √(-(2p + 2m ± √2q / √m))) / 2 - b / 4a");
+            }
+        }
+        if self.case != QuarticCase::Ferrari {
+            for (i, square) in self.squares.iter().enumerate() {
+                println!("Y{} = {:.*}", i + 1, precision, square);
+            }
+            println!("The solutions are ±√Y1 and ±√Y2:");
+        }
+        describe_roots(&self.roots, precision, variable);
+    }
 }
 
 pub struct MoreDegreeSolver {
-    degrees: Vec<(u32, f64)>,
+    polynomial: Polynomial,
+    zero_multiplicity: usize,
+    repeated: bool,
+    iterations: usize,
+    roots: Vec<Root>,
+    residuals: Vec<f64>,
 }
 
-impl<'a> MoreDegreeSolver {
+impl Default for MoreDegreeSolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MoreDegreeSolver {
+    const ITERATIONS_PER_DEGREE: usize = 100;
+    const WORK_BUDGET: usize = 100_000_000;
+    // Above it, even the first iterations would take too long
+    pub const MAX_DEGREE: u32 = 200;
+    const THRESHOLD: f64 = 0.000000000001;
+
     pub fn new() -> Self {
         MoreDegreeSolver {
-            degrees: Vec::new(),
+            polynomial: Polynomial::new(),
+            zero_multiplicity: 0,
+            repeated: false,
+            iterations: 0,
+            roots: Vec::new(),
+            residuals: Vec::new(),
+        }
+    }
+
+    // Horner's scheme on the dense coefficients, from the highest degree
+    fn horner(coeffs: &[f64], z: &Complex) -> Complex {
+        coeffs
+            .iter()
+            .rev()
+            .fold(Complex::new(0.0, 0.0), |acc, coeff| acc * *z + *coeff)
+    }
+
+    fn rounding_bound(magnitudes: &[f64], z: &Complex) -> f64 {
+        let bound = magnitudes
+            .iter()
+            .rev()
+            .fold(0.0, |acc, coeff| acc * z.abs() + coeff);
+        4.0 * f64::EPSILON * bound
+    }
+
+    fn durand_kerner(&mut self, polynomial: &Polynomial) -> (Vec<Complex>, bool) {
+        let degree = polynomial.degree() as usize;
+        let mut converged = degree == 0;
+        self.iterations = 0;
+        // X = 2^exponent * Y, with 2^exponent about the size of the largest root, keeps the
+        // powers of Y from overflowing; the polynomial in Y is made monic
+        let leading = polynomial.leading_coeff();
+        let exponent = polynomial
+            .terms()
+            .filter(|(k, coeff)| (*k as usize) < degree && **coeff != 0.0)
+            .map(|(k, coeff)| {
+                (coeff.abs().log2() - leading.abs().log2()) / (degree - k as usize) as f64
+            })
+            .fold(0.0, f64::max)
+            .ceil() as i64;
+        let coeffs: Vec<f64> = (0..=degree)
+            .map(|k| {
+                let shift = exponent * (k as i64 - degree as i64);
+                maths::scaled_ratio(polynomial.coeff(k as u32), leading, shift)
+            })
+            .collect();
+        let magnitudes: Vec<f64> = coeffs.iter().map(|coeff| coeff.abs()).collect();
+        // Starts on a circle enclosing every root (Fujiwara's bound), off any axis of symmetry
+        let radius = (0..degree)
+            .map(|k| (coeffs[k] / 2.0).abs().powf(1.0 / (degree - k) as f64))
+            .fold(0.0, f64::max)
+            * 2.0;
+        let mut roots: Vec<Complex> = (0..degree)
+            .map(|k| {
+                let angle = 2.0 * std::f64::consts::PI * k as f64 / degree as f64 + 0.4;
+                Complex::new(radius * angle.cos(), radius * angle.sin())
+            })
+            .collect();
+        // An iteration costs degree^2 operations, the work stays within the budget
+        let cap = (MoreDegreeSolver::ITERATIONS_PER_DEGREE * degree)
+            .min(MoreDegreeSolver::WORK_BUDGET / degree.max(1).pow(2));
+        while !converged && self.iterations < cap {
+            converged = true;
+            for i in 0..degree {
+                let denominator = roots
+                    .iter()
+                    .enumerate()
+                    .filter(|(j, _)| i != *j)
+                    .fold(Complex::new(1.0, 0.0), |acc, (_, other)| {
+                        acc * (roots[i] - *other)
+                    });
+                let value = MoreDegreeSolver::horner(&coeffs, &roots[i]);
+                let step = value / denominator;
+                roots[i] = roots[i] - step;
+                // Relative to the root, a root much smaller than the scale is still accurate
+                let root_converged = step.abs() <= MoreDegreeSolver::THRESHOLD * roots[i].abs()
+                    || value.abs() <= MoreDegreeSolver::rounding_bound(&magnitudes, &roots[i]);
+                if !root_converged {
+                    converged = false;
+                }
+            }
+            self.iterations += 1;
         }
+        let scale = Scaled::new(1.0, exponent).to_f64();
+        let roots = roots.into_iter().map(|root| root * scale).collect();
+        (roots, converged)
     }
 }
 
 impl Solver for MoreDegreeSolver {
-    fn solve(&mut self, degrees: &HashMap<u32, f64>) {
-        self.degrees = degrees
+    fn solve(&mut self, polynomial: &Polynomial, epsilon: f64) -> Result<Solution, SolveError> {
+        if polynomial.degree() > MoreDegreeSolver::MAX_DEGREE {
+            return Err(SolveError::TooBigDegree(polynomial.degree()));
+        }
+        self.polynomial = polynomial.clone();
+        let lowest =
+            |polynomial: &Polynomial| polynomial.terms().next().map_or(0, |(degree, _)| degree);
+        self.zero_multiplicity = lowest(polynomial) as usize;
+        let factorisation = factor::factorise_float(polynomial);
+        let distinct = match &factorisation {
+            Some(factorisation) => factorisation
+                .float_factors()
+                .into_iter()
+                .fold(Polynomial::constant(1.0), |acc, (part, _)| acc * part),
+            None => polynomial.clone(),
+        };
+        self.repeated = distinct.degree() < polynomial.degree();
+        let (deflated, _) = distinct.div_rem(&Polynomial::monomial(1.0, lowest(&distinct)));
+        if deflated.terms().any(|(_, coeff)| !coeff.is_finite()) {
+            return Err(SolveError::NotFinite);
+        }
+        let mut roots = vec![Complex::new(0.0, 0.0); lowest(&distinct) as usize];
+        let (approximations, converged) = self.durand_kerner(&deflated);
+        roots.extend(approximations);
+        self.roots = group_roots(polynomial, factorisation.as_ref(), &roots, epsilon);
+        self.residuals = self
+            .roots
+            .iter()
+            .map(|root| polynomial.evaluate_complex(&root.value).abs())
+            .collect();
+        if !converged {
+            return Err(SolveError::NoConvergence {
+                iterations: self.iterations,
+                residual: self.residuals.iter().cloned().fold(0.0, f64::max),
+            });
+        }
+        let mut solution = Solution::new(
+            polynomial,
+            polynomial.degree(),
+            None,
+            Roots::Roots(self.roots.clone()),
+            factorisation,
+        );
+        solution.convergence = Some(Convergence {
+            iterations: self.iterations,
+            residuals: self.residuals.clone(),
+        });
+        Ok(solution)
+    }
+
+    fn describe(&self, solution: &Solution, precision: usize) {
+        let variable = solution.variable;
+        println!("Reduced form: {:#} = 0", self.polynomial.named(variable));
+        describe_factored_form(solution);
+        println!("Polynomial degree: {}", self.polynomial.degree());
+        match self.polynomial.degree() {
+            0..=4 => println!("The closed form overflows the floating point range, the solutions are approximated with the Durand-Kerner method."),
+            _ => println!("The polynomial degree is stricly greater than 4, the solutions are approximated with the Durand-Kerner method."),
+        }
+        if self.zero_multiplicity > 0 {
+            println!(
+                "The lowest {} coefficient(s) are zero, {} = 0 is factored out first.",
+                self.zero_multiplicity, variable
+            );
+        }
+        if self.repeated {
+            println!("The polynomial has repeated roots, the method is applied to P / gcd(P, P') which has the same roots, all simple.");
+        }
+        println!(
+            "The method converged after {} iteration(s), the solutions are:",
+            self.iterations
+        );
+        for (root, residual) in self.roots.iter().zip(self.residuals.iter()) {
+            println!("{} = {:.*}", variable, precision, root);
+            println!("    |P({})| = {:.2e}", variable, residual);
+        }
+    }
+}
+
+// Returns the index of the factor that vanishes at the root, and the root refined on it
+fn refine_root(parts: &[(Polynomial, usize)], root: Complex) -> (usize, Complex) {
+    let residual = |part: &Polynomial| relative_residual(part, &root);
+    let (index, (part, _)) = match parts.iter().enumerate().min_by(|(_, (a, _)), (_, (b, _))| {
+        residual(a)
+            .partial_cmp(&residual(b))
+            .unwrap_or(Ordering::Equal)
+    }) {
+        Some(part) => part,
+        None => return (0, root),
+    };
+    // The factor has a simple root here, Newton's method converges quickly
+    (index, newton(part, root))
+}
+
+// Newton's method from an approximation of a simple root, kept when it does not wander off
+fn newton(polynomial: &Polynomial, root: Complex) -> Complex {
+    let derivative = polynomial.derivative();
+    let mut z = root;
+    for _ in 0..REFINE_ITERATIONS {
+        let step = polynomial.evaluate_complex(&z) / derivative.evaluate_complex(&z);
+        if !step.real.is_finite() || !step.imag.is_finite() {
+            break;
+        }
+        z = z - step;
+        if step.abs() <= f64::EPSILON * 1.0_f64.max(z.abs()) {
+            break;
+        }
+    }
+    match (z - root).abs() <= REFINE_BOUND * 1.0_f64.max(root.abs()) {
+        true => z,
+        false => root,
+    }
+}
+
+// Merges the closest approximations until there are as many as the distinct roots
+fn merge_closest(roots: &mut Vec<(usize, Complex)>, count: usize) {
+    while roots.len() > count.max(1) {
+        let mut closest = (0, 1, f64::INFINITY);
+        for i in 0..roots.len() {
+            for j in i + 1..roots.len() {
+                let distance = (roots[i].1 - roots[j].1).abs();
+                if distance < closest.2 {
+                    closest = (i, j, distance);
+                }
+            }
+        }
+        roots.remove(closest.1);
+    }
+}
+
+// |P(z)| relative to the sum of the absolute values of its terms
+fn relative_residual(polynomial: &Polynomial, z: &Complex) -> f64 {
+    match polynomial.evaluate_complex(z).abs() {
+        0.0 => 0.0,
+        value => value / polynomial.map(|coeff| coeff.abs()).evaluate(&z.abs()),
+    }
+}
+
+// The rounding errors split a root of multiplicity m by about epsilon^(1/m)
+fn cluster_radius(epsilon: f64, multiplicity: usize) -> f64 {
+    match multiplicity {
+        0..=2 => maths::sqrt(epsilon).unwrap_or(0.0),
+        _ => epsilon.powf(1.0 / multiplicity as f64),
+    }
+}
+
+fn centroid(cluster: &[(usize, Complex)]) -> Complex {
+    let sum = cluster
+        .iter()
+        .fold(Complex::new(0.0, 0.0), |acc, (_, root)| acc + *root);
+    sum / cluster.len() as f64
+}
+
+// Around the first root, the largest cluster that is a repeated root: its approximations lie
+// within the radius of its multiplicity from its centre, and P vanishes at the centre
+fn take_cluster(
+    polynomial: &Polynomial,
+    remaining: &mut Vec<(usize, Complex)>,
+    epsilon: f64,
+) -> (usize, Root) {
+    let seed = remaining[0].1;
+    remaining.sort_by(|(_, a), (_, b)| {
+        (*a - seed)
+            .abs()
+            .partial_cmp(&(*b - seed).abs())
+            .unwrap_or(Ordering::Equal)
+    });
+    let is_cluster = |cluster: &[(usize, Complex)]| {
+        let centre = centroid(cluster);
+        let radius = cluster_radius(epsilon, cluster.len()) * 1.0_f64.max(centre.abs());
+        cluster
             .iter()
-            .map(|(deg, val)| (*deg, *val))
-            .collect::<Vec<(u32, f64)>>();
-        self.degrees.sort_by_key(|&(deg, _)| deg);
-    }
-
-    fn describe(&self) {
-        print!("Reduced form: ");
-        for (i, (deg, val)) in self.degrees.iter().enumerate() {
-            if i == 0 {
-                print!(
-                    "{}{} * X^{} ",
-                    if *val < 0.0 { "- " } else { "" },
-                    val.abs(),
-                    deg
+            .all(|(_, root)| (*root - centre).abs() <= radius)
+            && relative_residual(polynomial, &centre) <= epsilon
+    };
+    let multiplicity = (2..=remaining.len())
+        .rev()
+        .find(|size| is_cluster(&remaining[..*size]))
+        .unwrap_or(1);
+    let cluster: Vec<(usize, Complex)> = remaining.drain(..multiplicity).collect();
+    let order = cluster.iter().map(|(order, _)| *order).min().unwrap_or(0);
+    remaining.sort_by_key(|(order, _)| *order);
+    // A root of multiplicity m is a simple root of the (m - 1)-th derivative
+    let mut root = match multiplicity {
+        1 => cluster[0].1,
+        _ => {
+            let derivative = (1..multiplicity).fold(polynomial.clone(), |acc, _| acc.derivative());
+            newton(&derivative, centroid(&cluster))
+        }
+    };
+    if root.imag.abs() <= cluster_radius(epsilon, multiplicity) * 1.0_f64.max(root.abs()) {
+        root.imag = 0.0;
+    }
+    (order, Root::new(root, multiplicity))
+}
+
+// The multiplicities come from the factors when they are known: each factor of degree d keeps
+// its d roots, however close, and a linear factor gives its root exactly. Otherwise the
+// approximations of a repeated root are grouped into clusters.
+fn group_roots(
+    polynomial: &Polynomial,
+    factorisation: Option<&Factorisation>,
+    roots: &[Complex],
+    epsilon: f64,
+) -> Vec<Root> {
+    let tolerance = cluster_radius(epsilon, 2);
+    let real_if_close = |mut root: Complex| {
+        if root.imag.abs() <= tolerance * 1.0_f64.max(root.abs()) {
+            root.imag = 0.0;
+        }
+        root
+    };
+    let parts = match factorisation {
+        Some(factorisation) => factorisation.float_factors(),
+        None => Vec::new(),
+    };
+    if parts.is_empty() {
+        let mut remaining: Vec<(usize, Complex)> = roots.iter().cloned().enumerate().collect();
+        let mut groups = Vec::new();
+        while !remaining.is_empty() {
+            groups.push(take_cluster(polynomial, &mut remaining, epsilon));
+        }
+        groups.sort_by_key(|(order, _)| *order);
+        return groups.into_iter().map(|(_, root)| root).collect();
+    }
+    let mut candidates: Vec<Vec<(usize, Complex)>> = vec![Vec::new(); parts.len()];
+    for (order, root) in roots.iter().enumerate() {
+        let (index, refined) = refine_root(&parts, *root);
+        candidates[index].push((order, refined));
+    }
+    let mut groups: Vec<(usize, Root)> = Vec::new();
+    for (index, ((part, multiplicity), mut candidates)) in parts.iter().zip(candidates).enumerate()
+    {
+        if part.degree() == 1 {
+            let order = candidates
+                .iter()
+                .map(|(order, _)| *order)
+                .min()
+                .unwrap_or(roots.len() + index);
+            let root = Complex::new(-part.coeff(0) / part.coeff(1), 0.0);
+            groups.push((order, Root::new(root, *multiplicity)));
+            continue;
+        }
+        merge_closest(&mut candidates, part.degree() as usize);
+        groups.extend(
+            candidates
+                .into_iter()
+                .map(|(order, root)| (order, Root::new(real_if_close(root), *multiplicity))),
+        );
+    }
+    groups.sort_by_key(|(order, _)| *order);
+    groups.into_iter().map(|(_, root)| root).collect()
+}
+
+// The highest multiplicity of the roots, known exactly from the square-free decomposition
+fn highest_multiplicity(factorisation: Option<&Factorisation>) -> Option<usize> {
+    factorisation.and_then(|factorisation| {
+        factorisation
+            .float_factors()
+            .iter()
+            .map(|(_, multiplicity)| *multiplicity)
+            .max()
+    })
+}
+
+pub fn format_multiplicity(multiplicity: usize) -> String {
+    match multiplicity {
+        1 => String::new(),
+        2 => String::from(" (double root)"),
+        3 => String::from(" (triple root)"),
+        4 => String::from(" (quadruple root)"),
+        _ => format!(" (root of multiplicity {})", multiplicity),
+    }
+}
+
+pub fn format_surd(z: &Surd, precision: usize) -> String {
+    match z.is_integer() {
+        true => format!("{}", z),
+        false => format!("{} ≈ {:.*}", z, precision, z.to_complex()),
+    }
+}
+
+pub fn describe_denominators(solution: &Solution) {
+    if !solution.denominators.is_empty() {
+        println!(
+            "{} appears in the denominator(s) {}, both sides are multiplied by them to get a polynomial equation.",
+            solution.variable,
+            solution.denominators.join(", ")
+        );
+    }
+}
+
+pub fn describe_rejections(solution: &Solution, precision: usize) {
+    if solution.rejected.is_empty() {
+        return;
+    }
+    match solution.roots {
+        Roots::AllReals => {
+            println!("Except the values that make a denominator zero:");
+            for rejection in solution.rejected.iter() {
+                println!(
+                    "{} = {:.*}, which makes the denominator {} zero",
+                    solution.variable, precision, rejection.value, rejection.denominator
                 );
-            } else {
-                print!(
-                    "{} {} * X^{} ",
-                    if *val < 0.0 { "-" } else { "+" },
-                    val.abs(),
-                    deg
+            }
+        }
+        _ => {
+            for rejection in solution.rejected.iter() {
+                println!(
+                    "{} = {:.*} is rejected, it makes the denominator {} zero",
+                    solution.variable, precision, rejection.value, rejection.denominator
                 );
             }
+            match &solution.roots {
+                Roots::Roots(roots) => {
+                    println!("The solutions of the original equation are:");
+                    describe_roots(roots, precision, solution.variable);
+                }
+                _ => println!("The original equation has no solution"),
+            }
         }
-        print!("= 0\n");
+    }
+}
+
+pub fn describe_factored_form(solution: &Solution) {
+    if let Some(factorisation) = &solution.factorisation {
         println!(
-            "Polynomial degree: {}",
-            self.degrees[self.degrees.len() - 1].0
+            "Factored form: {} = 0",
+            factorisation.clone().named(solution.variable)
         );
-        println!("The polynomial degree is stricly greater than 2, I can't solve.");
     }
 }
 
-pub fn choose_solver(degrees: &HashMap<u32, f64>) -> Box<dyn Solver> {
-    let mut degrees_vec = degrees
-        .iter()
-        .map(|(deg, val)| (*deg, *val))
-        .collect::<Vec<(u32, f64)>>();
-    degrees_vec.sort_by_key(|&(deg, _)| deg);
-    match degrees_vec[degrees.len() - 1].0 {
+fn describe_roots(roots: &[Root], precision: usize, variable: char) {
+    for root in roots.iter() {
+        println!("{} = {:.*}", variable, precision, root);
+    }
+}
+
+pub fn choose_solver(polynomial: &Polynomial) -> Box<dyn Solver> {
+    match polynomial.degree() {
         0 => Box::new(ZeroDegreeSolver::new()),
         1 => Box::new(OneDegreeSolver::new()),
         2 => Box::new(TwoDegreeSolver::new()),
+        3 => Box::new(ThreeDegreeSolver::new()),
+        4 => Box::new(FourDegreeSolver::new()),
         _ => Box::new(MoreDegreeSolver::new()),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPSILON: f64 = 0.000000000001;
+
+    fn solve(coeffs: &[f64]) -> Solution {
+        let polynomial = Polynomial::from_coeffs(coeffs);
+        choose_solver(&polynomial)
+            .solve(&polynomial, EPSILON)
+            .unwrap()
+    }
+
+    fn roots(solution: &Solution) -> Vec<Root> {
+        match &solution.roots {
+            Roots::Roots(roots) => roots.clone(),
+            _ => panic!("the equation has no isolated roots"),
+        }
+    }
+
+    fn assert_roots(solution: &Solution, expected: &[(f64, f64, usize)]) {
+        let roots = roots(solution);
+        assert_eq!(roots.len(), expected.len());
+        for (real, imag, multiplicity) in expected.iter() {
+            let z = Complex::new(*real, *imag);
+            assert!(
+                roots
+                    .iter()
+                    .any(|root| root.value.approx_eq(&z, 0.000000001)
+                        && root.multiplicity == *multiplicity),
+                "{:.17} (x{}) is not a root",
+                z,
+                multiplicity
+            );
+        }
+    }
+
+    fn assert_real_roots(solution: &Solution, expected: &[f64]) {
+        let roots = roots(solution);
+        assert_eq!(roots.len(), expected.len());
+        for root in expected.iter() {
+            assert!(
+                roots.iter().any(|other| other.value.imag == 0.0
+                    && (other.value.real - root).abs() <= 1e-12 * root.abs()),
+                "{:e} is not a root",
+                root
+            );
+        }
+    }
+
+    #[test]
+    fn quadratic_with_a_large_linear_coefficient() {
+        let solution = solve(&[1.0, 1e8, 1.0]);
+        assert_real_roots(&solution, &[-1e-8 - 1e-24, -1e8 + 1e-8]);
+        let solution = solve(&[1.0, -1e15, 1.0]);
+        assert_real_roots(&solution, &[1e-15, 1e15]);
+        let solution = solve(&[1e-10, 1.0, 1e-10]);
+        assert_real_roots(&solution, &[-1e-10, -1e10]);
+    }
+
+    #[test]
+    fn quadratic_with_huge_coefficients() {
+        let solution = solve(&[2e200, 3e200, 1e200]);
+        assert_real_roots(&solution, &[-1.0, -2.0]);
+        let discriminant = solution.discriminant.unwrap();
+        assert!(discriminant.value > 0.0);
+        assert_eq!(discriminant.to_string(), "1e400");
+        let solution = solve(&[5e300, 2e300, 1e300]);
+        assert_roots(&solution, &[(-1.0, 2.0, 1), (-1.0, -2.0, 1)]);
+        assert!(solution.discriminant.unwrap().value < 0.0);
+    }
+
+    #[test]
+    fn quadratic_with_tiny_coefficients() {
+        let solution = solve(&[-1e-200, 0.0, 1e-200]);
+        assert_real_roots(&solution, &[-1.0, 1.0]);
+        assert_eq!(solution.discriminant.unwrap().to_string(), "4e-400");
+    }
+
+    #[test]
+    fn quadratic_with_a_noisy_double_root() {
+        let solution = solve(&[0.01, -0.2, 1.0]);
+        assert_roots(&solution, &[(0.1, 0.0, 2)]);
+        assert_eq!(solution.discriminant.unwrap().value, 0.0);
+        let solution = solve(&[1.0 / 9.0, 2.0 / 3.0, 1.0]);
+        assert_roots(&solution, &[(-1.0 / 3.0, 0.0, 2)]);
+    }
+
+    #[test]
+    fn quadratic_with_complex_roots() {
+        let solution = solve(&[5.0, 2.0, 1.0]);
+        assert_roots(&solution, &[(-1.0, 2.0, 1), (-1.0, -2.0, 1)]);
+        assert_eq!(solution.discriminant.unwrap().to_f64(), -16.0);
+    }
+
+    #[test]
+    fn cubic_with_huge_coefficients() {
+        let solution = solve(&[1e100, 0.0, 0.0, 1e100]);
+        let half_sqrt_3 = 3.0_f64.sqrt() / 2.0;
+        assert_roots(
+            &solution,
+            &[
+                (-1.0, 0.0, 1),
+                (0.5, half_sqrt_3, 1),
+                (0.5, -half_sqrt_3, 1),
+            ],
+        );
+        assert!(solution.discriminant.unwrap().value < 0.0);
+    }
+
+    #[test]
+    fn cubic_with_tiny_coefficients() {
+        let solution = solve(&[-1e-120, 0.0, 0.0, 1e-120]);
+        let half_sqrt_3 = 3.0_f64.sqrt() / 2.0;
+        assert_roots(
+            &solution,
+            &[
+                (1.0, 0.0, 1),
+                (-0.5, half_sqrt_3, 1),
+                (-0.5, -half_sqrt_3, 1),
+            ],
+        );
+    }
+
+    #[test]
+    fn cubic_with_three_real_roots() {
+        let solution = solve(&[-6.0, 11.0, -6.0, 1.0]);
+        assert_roots(&solution, &[(1.0, 0.0, 1), (2.0, 0.0, 1), (3.0, 0.0, 1)]);
+        assert_eq!(solution.discriminant.unwrap().to_f64(), 4.0);
+    }
+
+    #[test]
+    fn cubic_with_repeated_roots() {
+        assert_roots(
+            &solve(&[2.0, -3.0, 0.0, 1.0]),
+            &[(1.0, 0.0, 2), (-2.0, 0.0, 1)],
+        );
+        assert_roots(&solve(&[-8.0, 12.0, -6.0, 1.0]), &[(2.0, 0.0, 3)]);
+    }
+
+    #[test]
+    fn quartic_with_huge_coefficients() {
+        let solution = solve(&[1e80, 0.0, 0.0, 1e80, 1e80]);
+        assert_eq!(roots(&solution).len(), 4);
+        for root in roots(&solution) {
+            let x = root.value;
+            let value = x * x * x * x + x * x * x + Complex::new(1.0, 0.0);
+            assert!(value.approx_eq(&Complex::new(0.0, 0.0), 0.000000001));
+        }
+    }
+
+    fn assert_relative_roots(solution: &Solution, expected: &[(f64, f64)]) {
+        let roots = roots(solution);
+        assert_eq!(roots.len(), expected.len());
+        for (real, imag) in expected.iter() {
+            let z = Complex::new(*real, *imag);
+            assert!(
+                roots
+                    .iter()
+                    .any(|root| (root.value - z).abs() <= 0.000000001 * z.abs()),
+                "{:e} + {:e}i is not a root",
+                real,
+                imag
+            );
+        }
+    }
+
+    #[test]
+    fn cubic_with_overflowing_depressed_coefficients() {
+        let solution = solve(&[1.0, 1e110, 0.0, 1.0]);
+        assert_relative_roots(&solution, &[(-1e-110, 0.0), (0.0, 1e55), (0.0, -1e55)]);
+        assert!(solution.convergence.is_some());
+        let solution = solve(&[1.0, 1.0, 0.0, 1e-300]);
+        assert_relative_roots(&solution, &[(-1.0, 0.0), (0.5, 1e150), (0.5, -1e150)]);
+    }
+
+    #[test]
+    fn triple_root_at_zero_is_not_negative() {
+        let mut solver = ThreeDegreeSolver::new();
+        solver
+            .solve(&Polynomial::from_coeffs(&[0.0, 0.0, 0.0, 1.0]), EPSILON)
+            .unwrap();
+        assert_eq!(format!("{:.2}", solver.z[0]), "0.00");
+    }
+
+    #[test]
+    fn quartic_with_overflowing_depressed_coefficients() {
+        for coeffs in [[1.0, 1e100, 0.0, 0.0, 1.0], [1.0, 1.0, 0.0, 0.0, 1e-200]] {
+            let solution = solve(&coeffs);
+            assert_eq!(roots(&solution).len(), 4);
+            assert!(solution.convergence.is_some());
+            // -e / d is the small root, the three others are the cube roots of -d / a
+            let small = -coeffs[0] / coeffs[1];
+            let large = -coeffs[1] / coeffs[4];
+            let cube_root = maths::cbrt(large.abs()).copysign(large);
+            let half_sqrt_3 = 3.0_f64.sqrt() / 2.0;
+            assert_relative_roots(
+                &solution,
+                &[
+                    (small, 0.0),
+                    (cube_root, 0.0),
+                    (-cube_root / 2.0, half_sqrt_3 * cube_root),
+                    (-cube_root / 2.0, -half_sqrt_3 * cube_root),
+                ],
+            );
+        }
+    }
+
+    #[test]
+    fn iteration_cap_scales_with_the_degree() {
+        for degree in [100, 200] {
+            let mut coeffs = vec![0.0; degree + 1];
+            coeffs[0] = 1.0;
+            coeffs[degree] = 1.0;
+            let solution = solve(&coeffs);
+            assert_eq!(roots(&solution).len(), degree);
+            let convergence = solution.convergence.unwrap();
+            assert!(convergence.iterations > 0);
+            assert_eq!(convergence.residuals.len(), degree);
+            assert!(convergence
+                .residuals
+                .iter()
+                .all(|residual| *residual < 0.000001));
+        }
+    }
+
+    #[test]
+    fn huge_degrees_fail_fast() {
+        for degree in [MoreDegreeSolver::MAX_DEGREE + 1, 1000, 4_000_000_000] {
+            let polynomial = Polynomial::monomial(1.0, degree) - Polynomial::constant(1.0);
+            let result = choose_solver(&polynomial).solve(&polynomial, EPSILON);
+            assert!(matches!(result, Err(SolveError::TooBigDegree(d)) if d == degree));
+        }
+    }
+
+    #[test]
+    fn closed_forms_report_no_convergence() {
+        assert!(solve(&[-1.0, 0.0, 1.0]).convergence.is_none());
+    }
+
+    fn assert_multiplicities(solution: &Solution) {
+        let total: usize = roots(solution).iter().map(|root| root.multiplicity).sum();
+        assert_eq!(total, solution.degree as usize);
+    }
+
+    #[test]
+    fn close_roots_are_kept() {
+        let solution = solve(&[1.0000001, -2.0000001, 1.0]);
+        assert_multiplicities(&solution);
+        assert_roots(&solution, &[(1.0, 0.0, 1), (1.0000001, 0.0, 1)]);
+        assert!(solution.discriminant.unwrap().value > 0.0);
+        let solution = solve(&[-1.0000001, 3.0000002, -3.0000001, 1.0]);
+        assert_multiplicities(&solution);
+        assert_roots(&solution, &[(1.0, 0.0, 2), (1.0000001, 0.0, 1)]);
+    }
+
+    #[test]
+    fn multiplicities_add_up_to_the_degree() {
+        for coeffs in [
+            vec![1.0, -2.0, 1.0],
+            vec![-1.0, 3.0, -3.0, 1.0],
+            vec![1.0, 0.0, -2.0, 0.0, 1.0],
+            vec![-1.0, -1.0, 2.0, 2.0, -1.0, -1.0],
+            vec![0.0, 0.0, 1.0, -2.0, 1.0, 0.0, 0.0],
+        ] {
+            assert_multiplicities(&solve(&coeffs));
+        }
+        assert_roots(
+            &solve(&[1.0, 0.0, -2.0, 0.0, 1.0]),
+            &[(1.0, 0.0, 2), (-1.0, 0.0, 2)],
+        );
+    }
+
+    #[test]
+    fn higher_multiplicities_without_factors() {
+        // 1/3 is not a decimal number, so the polynomials are not factorised
+        let third = Polynomial::from_coeffs(&[1.0 / 3.0, 1.0]);
+        for multiplicity in 3..=6 {
+            let polynomial = third.pow(multiplicity);
+            let solution = choose_solver(&polynomial)
+                .solve(&polynomial, EPSILON)
+                .unwrap();
+            assert!(solution.factorisation.is_none());
+            assert_roots(&solution, &[(-1.0 / 3.0, 0.0, multiplicity as usize)]);
+        }
+        let polynomial = third.pow(3) * Polynomial::from_coeffs(&[-1.0, 1.0]).pow(2);
+        let solution = choose_solver(&polynomial)
+            .solve(&polynomial, EPSILON)
+            .unwrap();
+        assert_roots(&solution, &[(-1.0 / 3.0, 0.0, 3), (1.0, 0.0, 2)]);
+    }
+
+    #[test]
+    fn distinct_small_roots_are_not_grouped() {
+        let solution = solve(&[-1e-20, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0]);
+        assert_eq!(roots(&solution).len(), 6);
+        assert_multiplicities(&solution);
+    }
+
+    #[test]
+    fn grouping_follows_epsilon() {
+        let polynomial = Polynomial::from_coeffs(&[1.0000001, -2.0000001, 1.0]);
+        let roots = [Complex::new(1.0, 0.0), Complex::new(1.0000001, 0.0)];
+        let grouped = group_roots(&polynomial, None, &roots, 0.000000000001);
+        assert_eq!(grouped.len(), 1);
+        assert_eq!(grouped[0].multiplicity, 2);
+        let grouped = group_roots(&polynomial, None, &roots, 0.0000000000000001);
+        assert_eq!(grouped.len(), 2);
+    }
+}